@@ -29,4 +29,6 @@ pub enum CredentialsError {
     HomeDir,
     #[error("Could not get valid credentials from STS, ENV, Profile or Instance metadata")]
     NoCredentials,
+    #[error("STS returned HTTP {0}: {1}")]
+    Sts(u16, String),
 }
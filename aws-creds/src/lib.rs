@@ -4,6 +4,12 @@
 mod credentials;
 pub use credentials::*;
 pub mod error;
+mod provider;
+pub use provider::*;
+#[cfg(feature = "sts")]
+mod sts;
+#[cfg(feature = "sts")]
+pub use sts::*;
 
 // Reexport for e.g. users who need to build Credentials
 pub use time;
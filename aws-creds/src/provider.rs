@@ -0,0 +1,117 @@
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+
+use crate::error::CredentialsError;
+use crate::{Credentials, REFRESH_MARGIN};
+
+/// Source of the credentials requests are signed with
+///
+/// `credentials` is called for every request, implementations that fetch credentials
+/// remotely should cache them, see `RefreshingProvider`.
+pub trait CredentialsProvider: fmt::Debug + Send + Sync {
+    fn credentials(&self) -> Result<Credentials, CredentialsError>;
+}
+
+/// Static credentials, never rotated
+impl CredentialsProvider for Credentials {
+    fn credentials(&self) -> Result<Credentials, CredentialsError> {
+        Ok(self.clone())
+    }
+}
+
+/// Caches the credentials returned by `fetch` and fetches new ones once they are within
+/// `REFRESH_MARGIN` of expiring. Credentials without an expiration are fetched once.
+///
+/// # Example
+/// ```no_run
+/// use awscreds::{Credentials, CredentialsProvider, RefreshingProvider};
+///
+/// let provider = RefreshingProvider::new(Credentials::from_instance_metadata);
+/// let credentials = provider.credentials().unwrap();
+/// ```
+pub struct RefreshingProvider<F> {
+    fetch: F,
+    cached: Mutex<Option<Credentials>>,
+}
+
+impl<F> RefreshingProvider<F>
+where
+    F: Fn() -> Result<Credentials, CredentialsError> + Send + Sync,
+{
+    pub fn new(fetch: F) -> RefreshingProvider<F> {
+        RefreshingProvider {
+            fetch,
+            cached: Mutex::new(None),
+        }
+    }
+}
+
+impl<F> CredentialsProvider for RefreshingProvider<F>
+where
+    F: Fn() -> Result<Credentials, CredentialsError> + Send + Sync,
+{
+    fn credentials(&self) -> Result<Credentials, CredentialsError> {
+        let mut cached = self.cached.lock().unwrap_or_else(PoisonError::into_inner);
+        match &*cached {
+            Some(credentials) if !credentials.expires_within(REFRESH_MARGIN) => {
+                Ok(credentials.clone())
+            }
+            _ => {
+                let credentials = (self.fetch)()?;
+                *cached = Some(credentials.clone());
+                Ok(credentials)
+            }
+        }
+    }
+}
+
+impl<F> fmt::Debug for RefreshingProvider<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RefreshingProvider")
+            .field("cached", &self.cached)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rfc3339OffsetDateTime;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use time::OffsetDateTime;
+
+    fn expiring_in(secs: u64) -> Credentials {
+        let mut credentials = Credentials::anonymous().unwrap();
+        credentials.expiration = Some(Rfc3339OffsetDateTime(
+            OffsetDateTime::now_utc() + Duration::from_secs(secs),
+        ));
+        credentials
+    }
+
+    #[test]
+    fn test_refreshing_provider_caches() {
+        let fetched = AtomicUsize::new(0);
+        let provider = RefreshingProvider::new(|| {
+            fetched.fetch_add(1, Ordering::SeqCst);
+            Ok(expiring_in(3600))
+        });
+
+        provider.credentials().unwrap();
+        provider.credentials().unwrap();
+        assert_eq!(fetched.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_refreshing_provider_refetches_before_expiry() {
+        let fetched = AtomicUsize::new(0);
+        let provider = RefreshingProvider::new(|| {
+            fetched.fetch_add(1, Ordering::SeqCst);
+            Ok(expiring_in(60))
+        });
+
+        provider.credentials().unwrap();
+        provider.credentials().unwrap();
+        assert_eq!(fetched.load(Ordering::SeqCst), 2);
+    }
+}
@@ -79,6 +79,10 @@ pub enum Region {
     MeSouth1,
     /// sa-east-1
     SaEast1,
+    /// us-gov-east-1, AWS GovCloud (US-East)
+    UsGovEast1,
+    /// us-gov-west-1, AWS GovCloud (US-West)
+    UsGovWest1,
     /// Digital Ocean nyc3
     DoNyc3,
     /// Digital Ocean ams3
@@ -97,10 +101,48 @@ pub enum Region {
     WaUsWest1,
     /// Wasabi eu-central-1
     WaEuCentral1,
-    /// Custom region
+    /// Wasabi us-central-1
+    WaUsCentral1,
+    /// Wasabi ca-central-1
+    WaCaCentral1,
+    /// Wasabi eu-central-2
+    WaEuCentral2,
+    /// Wasabi eu-west-1
+    WaEuWest1,
+    /// Wasabi eu-west-2
+    WaEuWest2,
+    /// Wasabi ap-northeast-1
+    WaApNortheast1,
+    /// Wasabi ap-northeast-2
+    WaApNortheast2,
+    /// Wasabi ap-southeast-1
+    WaApSoutheast1,
+    /// Wasabi ap-southeast-2
+    WaApSoutheast2,
+    /// Scaleway fr-par, parsed from `scw-fr-par`, plain `fr-par` stays a `Custom` region
+    ScwFrPar,
+    /// Scaleway nl-ams
+    ScwNlAms,
+    /// Scaleway pl-waw
+    ScwPlWaw,
+    /// Cloudflare R2
     R2 {
         account_id: String,
     },
+    /// Backblaze B2, `region` like `us-west-004`
+    B2 {
+        region: String,
+    },
+    /// Oracle Cloud Infrastructure Object Storage, through its S3 compatibility API, with the
+    /// Object Storage `namespace` of the tenancy
+    Oci {
+        namespace: String,
+        region: String,
+    },
+    /// IBM Cloud Object Storage, `region` like `us-south` or `eu-de`
+    Ibm {
+        region: String,
+    },
     Custom {
         region: String,
         endpoint: String,
@@ -135,6 +177,8 @@ impl fmt::Display for Region {
             SaEast1 => write!(f, "sa-east-1"),
             IlCentral1 => write!(f, "il-central-1"),
             MeSouth1 => write!(f, "me-south-1"),
+            UsGovEast1 => write!(f, "us-gov-east-1"),
+            UsGovWest1 => write!(f, "us-gov-west-1"),
             DoNyc3 => write!(f, "nyc3"),
             DoAms3 => write!(f, "ams3"),
             DoSgp1 => write!(f, "sgp1"),
@@ -144,7 +188,22 @@ impl fmt::Display for Region {
             WaUsEast2 => write!(f, "us-east-2"),
             WaUsWest1 => write!(f, "us-west-1"),
             WaEuCentral1 => write!(f, "eu-central-1"),
+            WaUsCentral1 => write!(f, "us-central-1"),
+            WaCaCentral1 => write!(f, "ca-central-1"),
+            WaEuCentral2 => write!(f, "eu-central-2"),
+            WaEuWest1 => write!(f, "eu-west-1"),
+            WaEuWest2 => write!(f, "eu-west-2"),
+            WaApNortheast1 => write!(f, "ap-northeast-1"),
+            WaApNortheast2 => write!(f, "ap-northeast-2"),
+            WaApSoutheast1 => write!(f, "ap-southeast-1"),
+            WaApSoutheast2 => write!(f, "ap-southeast-2"),
+            ScwFrPar => write!(f, "fr-par"),
+            ScwNlAms => write!(f, "nl-ams"),
+            ScwPlWaw => write!(f, "pl-waw"),
             R2 { .. } => write!(f, "auto"),
+            B2 { ref region } | Oci { ref region, .. } | Ibm { ref region } => {
+                write!(f, "{}", region)
+            }
             Custom { ref region, .. } => write!(f, "{}", region),
         }
     }
@@ -180,6 +239,8 @@ impl FromStr for Region {
             "sa-east-1" => Ok(SaEast1),
             "il-central-1" => Ok(IlCentral1),
             "me-south-1" => Ok(MeSouth1),
+            "us-gov-east-1" => Ok(UsGovEast1),
+            "us-gov-west-1" => Ok(UsGovWest1),
             "nyc3" => Ok(DoNyc3),
             "ams3" => Ok(DoAms3),
             "sgp1" => Ok(DoSgp1),
@@ -190,6 +251,24 @@ impl FromStr for Region {
             "wa-us-east-2" => Ok(WaUsEast2),
             "wa-us-west-1" => Ok(WaUsWest1),
             "wa-eu-central-1" => Ok(WaEuCentral1),
+            "wa-us-central-1" => Ok(WaUsCentral1),
+            "wa-ca-central-1" => Ok(WaCaCentral1),
+            "wa-eu-central-2" => Ok(WaEuCentral2),
+            "wa-eu-west-1" => Ok(WaEuWest1),
+            "wa-eu-west-2" => Ok(WaEuWest2),
+            "wa-ap-northeast-1" => Ok(WaApNortheast1),
+            "wa-ap-northeast-2" => Ok(WaApNortheast2),
+            "wa-ap-southeast-1" => Ok(WaApSoutheast1),
+            "wa-ap-southeast-2" => Ok(WaApSoutheast2),
+            "scw-fr-par" => Ok(ScwFrPar),
+            "scw-nl-ams" => Ok(ScwNlAms),
+            "scw-pl-waw" => Ok(ScwPlWaw),
+            x if x.starts_with("b2-") => Ok(B2 {
+                region: x["b2-".len()..].to_string(),
+            }),
+            x if x.starts_with("ibm-") => Ok(Ibm {
+                region: x["ibm-".len()..].to_string(),
+            }),
             x => Ok(Custom {
                 region: x.to_string(),
                 endpoint: x.to_string(),
@@ -228,6 +307,8 @@ impl Region {
             SaEast1 => String::from("s3-sa-east-1.amazonaws.com"),
             IlCentral1 => String::from("s3.il-central-1.amazonaws.com"),
             MeSouth1 => String::from("s3-me-south-1.amazonaws.com"),
+            UsGovEast1 => String::from("s3.us-gov-east-1.amazonaws.com"),
+            UsGovWest1 => String::from("s3.us-gov-west-1.amazonaws.com"),
             DoNyc3 => String::from("nyc3.digitaloceanspaces.com"),
             DoAms3 => String::from("ams3.digitaloceanspaces.com"),
             DoSgp1 => String::from("sgp1.digitaloceanspaces.com"),
@@ -237,11 +318,81 @@ impl Region {
             WaUsEast2 => String::from("s3.us-east-2.wasabisys.com"),
             WaUsWest1 => String::from("s3.us-west-1.wasabisys.com"),
             WaEuCentral1 => String::from("s3.eu-central-1.wasabisys.com"),
+            WaUsCentral1 => String::from("s3.us-central-1.wasabisys.com"),
+            WaCaCentral1 => String::from("s3.ca-central-1.wasabisys.com"),
+            WaEuCentral2 => String::from("s3.eu-central-2.wasabisys.com"),
+            WaEuWest1 => String::from("s3.eu-west-1.wasabisys.com"),
+            WaEuWest2 => String::from("s3.eu-west-2.wasabisys.com"),
+            WaApNortheast1 => String::from("s3.ap-northeast-1.wasabisys.com"),
+            WaApNortheast2 => String::from("s3.ap-northeast-2.wasabisys.com"),
+            WaApSoutheast1 => String::from("s3.ap-southeast-1.wasabisys.com"),
+            WaApSoutheast2 => String::from("s3.ap-southeast-2.wasabisys.com"),
+            ScwFrPar => String::from("s3.fr-par.scw.cloud"),
+            ScwNlAms => String::from("s3.nl-ams.scw.cloud"),
+            ScwPlWaw => String::from("s3.pl-waw.scw.cloud"),
             R2 { ref account_id } => format!("{}.r2.cloudflarestorage.com", account_id),
+            B2 { ref region } => format!("s3.{}.backblazeb2.com", region),
+            Oci {
+                ref namespace,
+                ref region,
+            } => format!(
+                "{}.compat.objectstorage.{}.oraclecloud.com",
+                namespace, region
+            ),
+            Ibm { ref region } => format!("s3.{}.cloud-object-storage.appdomain.cloud", region),
             Custom { ref endpoint, .. } => endpoint.to_string(),
         }
     }
 
+    /// Whether the region is one of AWS, in the `amazonaws.com` or `amazonaws.com.cn` domain.
+    pub fn is_aws(&self) -> bool {
+        self.aws_endpoint("s3").is_some()
+    }
+
+    /// Whether buckets of the region are better addressed with path-style URLs, as the S3
+    /// compatibility API of OCI requires and R2 works best with.
+    pub fn prefers_path_style(&self) -> bool {
+        matches!(self, Region::R2 { .. } | Region::Oci { .. })
+    }
+
+    /// Dual-stack (IPv4 and IPv6) endpoint of an AWS region, `s3.dualstack.<region>.amazonaws.com`.
+    /// `None` for other providers, and custom regions with an endpoint outside of AWS.
+    pub fn dualstack_endpoint(&self) -> Option<String> {
+        self.aws_endpoint("s3.dualstack")
+    }
+
+    /// FIPS 140-2 endpoint of an AWS region, `s3-fips.<region>.amazonaws.com`. Only the US,
+    /// GovCloud included, and Canada regions have one.
+    pub fn fips_endpoint(&self) -> Option<String> {
+        if !self.has_fips() {
+            return None;
+        }
+        self.aws_endpoint("s3-fips")
+    }
+
+    /// FIPS endpoint reachable over IPv6, `s3-fips.dualstack.<region>.amazonaws.com`.
+    pub fn fips_dualstack_endpoint(&self) -> Option<String> {
+        if !self.has_fips() {
+            return None;
+        }
+        self.aws_endpoint("s3-fips.dualstack")
+    }
+
+    fn has_fips(&self) -> bool {
+        let region = self.to_string();
+        region.starts_with("us-") || region.starts_with("ca-")
+    }
+
+    /// `<prefix>.<region>.<domain>` for regions with an endpoint in an AWS partition, the
+    /// `amazonaws.com` one or the `amazonaws.com.cn` one of China.
+    fn aws_endpoint(&self, prefix: &str) -> Option<String> {
+        let host = self.host();
+        ["amazonaws.com", "amazonaws.com.cn"]
+            .iter()
+            .find(|domain| host.ends_with(&format!(".{}", domain)))
+            .map(|domain| format!("{}.{}.{}", prefix, self, domain))
+    }
+
     pub fn scheme(&self) -> String {
         match *self {
             Region::Custom { ref endpoint, .. } => match endpoint.find("://") {
@@ -305,3 +456,99 @@ fn test_region_eu_central_2() {
     let region = "eu-central-2".parse::<Region>().unwrap();
     assert_eq!(region.endpoint(), "s3.eu-central-2.amazonaws.com");
 }
+
+#[test]
+fn test_dualstack_endpoint() {
+    assert_eq!(
+        Region::UsEast1.dualstack_endpoint().as_deref(),
+        Some("s3.dualstack.us-east-1.amazonaws.com")
+    );
+    assert_eq!(
+        Region::CnNorth1.dualstack_endpoint().as_deref(),
+        Some("s3.dualstack.cn-north-1.amazonaws.com.cn")
+    );
+    let gov = Region::Custom {
+        region: "us-gov-west-1".to_string(),
+        endpoint: "https://s3.us-gov-west-1.amazonaws.com".to_string(),
+    };
+    assert_eq!(
+        gov.dualstack_endpoint().as_deref(),
+        Some("s3.dualstack.us-gov-west-1.amazonaws.com")
+    );
+    assert!(Region::DoNyc3.dualstack_endpoint().is_none());
+    let minio = Region::Custom {
+        region: "us-east-1".to_string(),
+        endpoint: "http://localhost:9000".to_string(),
+    };
+    assert!(minio.dualstack_endpoint().is_none());
+}
+
+#[test]
+fn test_gov_cloud_and_fips_endpoints() {
+    let region = "us-gov-west-1".parse::<Region>().unwrap();
+    assert_eq!(region, Region::UsGovWest1);
+    assert_eq!(region.to_string(), "us-gov-west-1");
+    assert_eq!(region.endpoint(), "s3.us-gov-west-1.amazonaws.com");
+    assert_eq!(
+        region.fips_endpoint().as_deref(),
+        Some("s3-fips.us-gov-west-1.amazonaws.com")
+    );
+    assert_eq!(
+        Region::UsEast1.fips_dualstack_endpoint().as_deref(),
+        Some("s3-fips.dualstack.us-east-1.amazonaws.com")
+    );
+    assert_eq!(
+        Region::CaCentral1.fips_endpoint().as_deref(),
+        Some("s3-fips.ca-central-1.amazonaws.com")
+    );
+    // Neither outside of North America, nor in China
+    assert!(Region::EuWest1.fips_endpoint().is_none());
+    assert!(Region::CnNorth1.fips_endpoint().is_none());
+    assert!(Region::DoNyc3.fips_endpoint().is_none());
+    assert!(region.is_aws());
+    assert!(!Region::DoNyc3.is_aws());
+}
+
+#[test]
+fn test_provider_regions() {
+    let region = "wa-ap-northeast-1".parse::<Region>().unwrap();
+    assert_eq!(region, Region::WaApNortheast1);
+    assert_eq!(region.endpoint(), "s3.ap-northeast-1.wasabisys.com");
+    assert_eq!(region.to_string(), "ap-northeast-1");
+
+    let region = "scw-fr-par".parse::<Region>().unwrap();
+    assert_eq!(region, Region::ScwFrPar);
+    assert_eq!(region.endpoint(), "s3.fr-par.scw.cloud");
+    assert_eq!(region.to_string(), "fr-par");
+    // Bare Scaleway names stay custom regions, as before the presets
+    assert_eq!(
+        "fr-par".parse::<Region>().unwrap(),
+        Region::Custom {
+            region: "fr-par".to_string(),
+            endpoint: "fr-par".to_string()
+        }
+    );
+
+    let region = "b2-us-west-004".parse::<Region>().unwrap();
+    assert_eq!(region.endpoint(), "s3.us-west-004.backblazeb2.com");
+    assert_eq!(region.to_string(), "us-west-004");
+
+    let region = "ibm-eu-de".parse::<Region>().unwrap();
+    assert_eq!(
+        region.endpoint(),
+        "s3.eu-de.cloud-object-storage.appdomain.cloud"
+    );
+    assert_eq!(region.to_string(), "eu-de");
+
+    let region = Region::Oci {
+        namespace: "axaxnpcrorw5".to_string(),
+        region: "eu-frankfurt-1".to_string(),
+    };
+    assert_eq!(
+        region.endpoint(),
+        "axaxnpcrorw5.compat.objectstorage.eu-frankfurt-1.oraclecloud.com"
+    );
+    assert_eq!(region.to_string(), "eu-frankfurt-1");
+    assert!(region.prefers_path_style());
+    assert!(!Region::ScwNlAms.prefers_path_style());
+}
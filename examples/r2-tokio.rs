@@ -14,8 +14,7 @@ async fn main() -> Result<(), S3Error> {
             account_id: "valid-account-id".to_string(),
         },
         Credentials::default()?,
-    )?
-    .with_path_style();
+    )?;
 
     let s3_path = "test.file";
     let test = b"I'm going to S3!";
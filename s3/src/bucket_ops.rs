@@ -1,36 +1,77 @@
 use crate::error::S3Error;
+use crate::serde_types::AccessControlPolicy;
 use crate::{Bucket, Region};
 
 /// [AWS Documentation](https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html#CannedACL)
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
-pub enum CannedBucketAcl {
+pub enum CannedAcl {
     Private,
     PublicRead,
     PublicReadWrite,
+    AwsExecRead,
     AuthenticatedRead,
+    BucketOwnerRead,
+    BucketOwnerFullControl,
+    LogDeliveryWrite,
     Custom(String),
 }
 
+/// Canned ACLs used to be bucket specific, kept for backwards compatibility.
+pub type CannedBucketAcl = CannedAcl;
+
 use http::header::HeaderName;
 use http::HeaderMap;
 use std::fmt;
 
-impl fmt::Display for CannedBucketAcl {
+impl fmt::Display for CannedAcl {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            CannedBucketAcl::Private => write!(f, "private"),
-            CannedBucketAcl::PublicRead => write!(f, "public-read"),
-            CannedBucketAcl::PublicReadWrite => write!(f, "public-read-write"),
-            CannedBucketAcl::AuthenticatedRead => write!(f, "authenticated-read"),
-            CannedBucketAcl::Custom(policy) => write!(f, "{policy}"),
+            CannedAcl::Private => write!(f, "private"),
+            CannedAcl::PublicRead => write!(f, "public-read"),
+            CannedAcl::PublicReadWrite => write!(f, "public-read-write"),
+            CannedAcl::AwsExecRead => write!(f, "aws-exec-read"),
+            CannedAcl::AuthenticatedRead => write!(f, "authenticated-read"),
+            CannedAcl::BucketOwnerRead => write!(f, "bucket-owner-read"),
+            CannedAcl::BucketOwnerFullControl => write!(f, "bucket-owner-full-control"),
+            CannedAcl::LogDeliveryWrite => write!(f, "log-delivery-write"),
+            CannedAcl::Custom(policy) => write!(f, "{policy}"),
         }
     }
 }
 
+/// An ACL to apply with `PutObjectAcl` or `PutBucketAcl`, either canned or a full list of grants
+#[derive(Clone, Debug)]
+pub enum Acl {
+    Canned(CannedAcl),
+    Policy(AccessControlPolicy),
+}
+
+impl Acl {
+    /// XML request body, canned ACLs are sent as a header instead.
+    pub fn body(&self) -> Option<String> {
+        match self {
+            Acl::Canned(_) => None,
+            Acl::Policy(policy) => Some(policy.to_string()),
+        }
+    }
+}
+
+impl From<CannedAcl> for Acl {
+    fn from(acl: CannedAcl) -> Self {
+        Acl::Canned(acl)
+    }
+}
+
+impl From<AccessControlPolicy> for Acl {
+    fn from(policy: AccessControlPolicy) -> Self {
+        Acl::Policy(policy)
+    }
+}
+
 /// [AWS Documentation](https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html)
 #[allow(dead_code)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BucketAcl {
     Id { id: String },
     Uri { uri: String },
@@ -47,10 +88,44 @@ impl fmt::Display for BucketAcl {
     }
 }
 
+/// [AWS Documentation](https://docs.aws.amazon.com/AmazonS3/latest/userguide/about-object-ownership.html)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectOwnership {
+    /// ACLs are disabled, the bucket owner owns every object
+    BucketOwnerEnforced,
+    /// The bucket owner owns objects uploaded with the `bucket-owner-full-control` ACL
+    BucketOwnerPreferred,
+    /// The uploading account owns the object
+    ObjectWriter,
+}
+
+impl fmt::Display for ObjectOwnership {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectOwnership::BucketOwnerEnforced => write!(f, "BucketOwnerEnforced"),
+            ObjectOwnership::BucketOwnerPreferred => write!(f, "BucketOwnerPreferred"),
+            ObjectOwnership::ObjectWriter => write!(f, "ObjectWriter"),
+        }
+    }
+}
+
+/// Options for `CreateBucket`, `Default` is `private()`
+///
+/// ```
+/// use s3::BucketConfiguration;
+/// use s3::bucket_ops::{CannedAcl, ObjectOwnership};
+///
+/// let mut config = BucketConfiguration::public();
+/// config.set_location_constraint("eu-central-1".parse().unwrap());
+/// config.set_acl(CannedAcl::BucketOwnerFullControl);
+/// config.set_object_lock_enabled(true);
+/// config.set_object_ownership(ObjectOwnership::BucketOwnerPreferred);
+/// ```
 #[derive(Clone, Debug)]
 pub struct BucketConfiguration {
-    acl: Option<CannedBucketAcl>,
+    acl: Option<CannedAcl>,
     object_lock_enabled: bool,
+    object_ownership: Option<ObjectOwnership>,
     grant_full_control: Option<Vec<BucketAcl>>,
     grant_read: Option<Vec<BucketAcl>>,
     grant_read_acp: Option<Vec<BucketAcl>>,
@@ -65,7 +140,7 @@ impl Default for BucketConfiguration {
     }
 }
 
-fn acl_list(acl: &[BucketAcl]) -> String {
+pub(crate) fn acl_list(acl: &[BucketAcl]) -> String {
     acl.iter()
         .map(|x| x.to_string())
         .collect::<Vec<String>>()
@@ -75,7 +150,7 @@ fn acl_list(acl: &[BucketAcl]) -> String {
 impl BucketConfiguration {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        acl: Option<CannedBucketAcl>,
+        acl: Option<CannedAcl>,
         object_lock_enabled: bool,
         grant_full_control: Option<Vec<BucketAcl>>,
         grant_read: Option<Vec<BucketAcl>>,
@@ -87,6 +162,7 @@ impl BucketConfiguration {
         Self {
             acl,
             object_lock_enabled,
+            object_ownership: None,
             grant_full_control,
             grant_read,
             grant_read_acp,
@@ -100,6 +176,7 @@ impl BucketConfiguration {
         BucketConfiguration {
             acl: None,
             object_lock_enabled: false,
+            object_ownership: None,
             grant_full_control: None,
             grant_read: None,
             grant_read_acp: None,
@@ -111,8 +188,9 @@ impl BucketConfiguration {
 
     pub fn private() -> Self {
         BucketConfiguration {
-            acl: Some(CannedBucketAcl::Private),
+            acl: Some(CannedAcl::Private),
             object_lock_enabled: false,
+            object_ownership: None,
             grant_full_control: None,
             grant_read: None,
             grant_read_acp: None,
@@ -122,17 +200,58 @@ impl BucketConfiguration {
         }
     }
 
+    /// R2 buckets are private, without ACL headers. The private ACL, see `private()`, is left
+    /// out, others are an `S3Error::R2Unsupported`.
+    pub(crate) fn without_private_acl(&mut self) -> Result<(), S3Error> {
+        let grants = [
+            &self.grant_full_control,
+            &self.grant_read,
+            &self.grant_read_acp,
+            &self.grant_write,
+            &self.grant_write_acp,
+        ];
+        if !matches!(self.acl, None | Some(CannedAcl::Private))
+            || grants.iter().any(|grant| grant.is_some())
+        {
+            return Err(S3Error::R2Unsupported("ACLs"));
+        }
+        self.acl = None;
+        Ok(())
+    }
+
     pub fn set_region(&mut self, region: Region) {
         self.set_location_constraint(region)
     }
 
+    /// Region to create the bucket in, `Bucket::create` uses the region of the bucket if unset
     pub fn set_location_constraint(&mut self, region: Region) {
         self.location_constraint = Some(region)
     }
 
+    pub fn location_constraint(&self) -> Option<&Region> {
+        self.location_constraint.as_ref()
+    }
+
+    pub fn set_acl(&mut self, acl: CannedAcl) {
+        self.acl = Some(acl)
+    }
+
+    /// `BucketOwnerEnforced` disables ACLs, only `private` or `bucket-owner-full-control` go with it.
+    pub fn set_object_ownership(&mut self, object_ownership: ObjectOwnership) {
+        self.object_ownership = Some(object_ownership)
+    }
+
+    /// Object Lock can only be enabled when the bucket is created.
+    pub fn set_object_lock_enabled(&mut self, object_lock_enabled: bool) {
+        self.object_lock_enabled = object_lock_enabled
+    }
+
     pub fn location_constraint_payload(&self) -> Option<String> {
         if let Some(ref location_constraint) = self.location_constraint {
-            if location_constraint == &Region::UsEast1 {
+            // R2 places buckets by itself
+            if location_constraint == &Region::UsEast1
+                || matches!(location_constraint, Region::R2 { .. })
+            {
                 return None;
             }
             Some(format!(
@@ -158,6 +277,12 @@ impl BucketConfiguration {
                 "Enabled".to_string().parse()?,
             );
         }
+        if let Some(object_ownership) = self.object_ownership {
+            headers.insert(
+                HeaderName::from_static("x-amz-object-ownership"),
+                object_ownership.to_string().parse()?,
+            );
+        }
         if let Some(ref value) = self.grant_full_control {
             headers.insert(
                 HeaderName::from_static("x-amz-grant-full-control"),
@@ -197,6 +322,8 @@ pub struct CreateBucketResponse {
     pub bucket: Bucket,
     pub response_text: String,
     pub response_code: u16,
+    /// `Location` header of the response, `/<name>` or the bucket URL
+    pub location: Option<String>,
 }
 
 impl CreateBucketResponse {
@@ -315,3 +442,34 @@ mod list_buckets {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{BucketConfiguration, CannedAcl, ObjectOwnership};
+    use http::HeaderMap;
+
+    #[test]
+    fn test_bucket_configuration_headers() {
+        let mut config = BucketConfiguration::default();
+        config.set_location_constraint("eu-central-1".parse().unwrap());
+        config.set_acl(CannedAcl::BucketOwnerFullControl);
+        config.set_object_lock_enabled(true);
+        config.set_object_ownership(ObjectOwnership::BucketOwnerPreferred);
+
+        let mut headers = HeaderMap::new();
+        config.add_headers(&mut headers).unwrap();
+        assert_eq!(headers["x-amz-acl"], "bucket-owner-full-control");
+        assert_eq!(headers["x-amz-bucket-object-lock-enabled"], "Enabled");
+        assert_eq!(headers["x-amz-object-ownership"], "BucketOwnerPreferred");
+        assert_eq!(
+            config.location_constraint_payload().unwrap(),
+            "<CreateBucketConfiguration><LocationConstraint>eu-central-1</LocationConstraint></CreateBucketConfiguration>"
+        );
+
+        let mut headers = HeaderMap::new();
+        BucketConfiguration::public()
+            .add_headers(&mut headers)
+            .unwrap();
+        assert!(headers.is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+//! [Flexible checksums][link] of uploaded and downloaded object data
+//!
+//! [link]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/checking-object-integrity.html
+
+use std::fmt;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Algorithm of the `x-amz-checksum-*` headers, see `Bucket::with_checksum_algorithm`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChecksumAlgorithm {
+    #[serde(rename = "CRC32")]
+    Crc32,
+    #[serde(rename = "CRC32C")]
+    Crc32c,
+    #[serde(rename = "SHA1")]
+    Sha1,
+    #[serde(rename = "SHA256")]
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    pub const ALL: [ChecksumAlgorithm; 4] = [
+        ChecksumAlgorithm::Crc32,
+        ChecksumAlgorithm::Crc32c,
+        ChecksumAlgorithm::Sha1,
+        ChecksumAlgorithm::Sha256,
+    ];
+
+    /// Name of the algorithm in `x-amz-sdk-checksum-algorithm` and `x-amz-checksum-algorithm`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Crc32 => "CRC32",
+            ChecksumAlgorithm::Crc32c => "CRC32C",
+            ChecksumAlgorithm::Sha1 => "SHA1",
+            ChecksumAlgorithm::Sha256 => "SHA256",
+        }
+    }
+
+    /// Header carrying checksums of this algorithm
+    pub fn header_name(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Crc32 => "x-amz-checksum-crc32",
+            ChecksumAlgorithm::Crc32c => "x-amz-checksum-crc32c",
+            ChecksumAlgorithm::Sha1 => "x-amz-checksum-sha1",
+            ChecksumAlgorithm::Sha256 => "x-amz-checksum-sha256",
+        }
+    }
+
+    /// Element of the checksum in `CompleteMultipartUpload` parts
+    pub(crate) fn xml_element(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Crc32 => "ChecksumCRC32",
+            ChecksumAlgorithm::Crc32c => "ChecksumCRC32C",
+            ChecksumAlgorithm::Sha1 => "ChecksumSHA1",
+            ChecksumAlgorithm::Sha256 => "ChecksumSHA256",
+        }
+    }
+
+    /// Base64 encoded checksum of `data`, as sent in the checksum header.
+    pub fn checksum(&self, data: &[u8]) -> String {
+        let mut hasher = Hasher::new(*self);
+        hasher.update(data);
+        hasher.finish()
+    }
+}
+
+impl fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A checksum and its algorithm, kept with an uploaded `Part` to complete the upload with
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecksumValue {
+    pub algorithm: ChecksumAlgorithm,
+    /// Base64 encoded checksum.
+    pub value: String,
+}
+
+impl ChecksumValue {
+    pub fn new(algorithm: ChecksumAlgorithm, data: &[u8]) -> ChecksumValue {
+        ChecksumValue {
+            algorithm,
+            value: algorithm.checksum(data),
+        }
+    }
+}
+
+/// Incremental checksum of a body read or sent in chunks
+#[derive(Clone)]
+pub(crate) enum Hasher {
+    Crc32(u32),
+    Crc32c(u32),
+    Sha1(Sha1),
+    Sha256(Sha256),
+}
+
+impl Hasher {
+    pub(crate) fn new(algorithm: ChecksumAlgorithm) -> Hasher {
+        match algorithm {
+            ChecksumAlgorithm::Crc32 => Hasher::Crc32(!0),
+            ChecksumAlgorithm::Crc32c => Hasher::Crc32c(!0),
+            ChecksumAlgorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+            ChecksumAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Crc32(crc) => *crc = crc_update(&CRC32_TABLE, *crc, data),
+            Hasher::Crc32c(crc) => *crc = crc_update(&CRC32C_TABLE, *crc, data),
+            Hasher::Sha1(sha) => sha.update(data),
+            Hasher::Sha256(sha) => sha.update(data),
+        }
+    }
+
+    /// Base64 encoded checksum of everything passed to `update`.
+    pub(crate) fn finish(self) -> String {
+        match self {
+            Hasher::Crc32(crc) | Hasher::Crc32c(crc) => {
+                general_purpose::STANDARD.encode((!crc).to_be_bytes())
+            }
+            Hasher::Sha1(sha) => general_purpose::STANDARD.encode(sha.finalize()),
+            Hasher::Sha256(sha) => general_purpose::STANDARD.encode(sha.finalize()),
+        }
+    }
+}
+
+/// Reflected CRC-32 of IEEE 802.3
+static CRC32_TABLE: [u32; 256] = crc_table(0xEDB8_8320);
+/// Reflected CRC-32C, Castagnoli
+static CRC32C_TABLE: [u32; 256] = crc_table(0x82F6_3B78);
+
+const fn crc_table(polynomial: u32) -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ polynomial
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+}
+
+fn crc_update(table: &[u32; 256], mut crc: u32, data: &[u8]) -> u32 {
+    for byte in data {
+        crc = table[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+#[cfg(test)]
+mod test {
+    use base64::engine::general_purpose;
+    use base64::Engine;
+
+    use super::{ChecksumAlgorithm, Hasher};
+
+    #[test]
+    fn test_checksums() {
+        let crc = |algorithm: ChecksumAlgorithm| {
+            let checksum = general_purpose::STANDARD
+                .decode(algorithm.checksum(b"123456789"))
+                .unwrap();
+            u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]])
+        };
+        // The check values of the CRC catalogue
+        assert_eq!(crc(ChecksumAlgorithm::Crc32), 0xCBF4_3926);
+        assert_eq!(crc(ChecksumAlgorithm::Crc32c), 0xE306_9283);
+
+        assert_eq!(
+            ChecksumAlgorithm::Sha1.checksum(b"hello world"),
+            "Kq5sNclPz7QV2+lfQIuc6R7oRu0="
+        );
+        assert_eq!(
+            ChecksumAlgorithm::Sha256.checksum(b"hello world"),
+            "uU0nuZNNPgilLlLX2n2r+sSE7+N6U4DukIj3rOLvzek="
+        );
+        assert_eq!(ChecksumAlgorithm::Crc32.checksum(b""), "AAAAAA==");
+    }
+
+    #[test]
+    fn test_hasher_chunks() {
+        for algorithm in ChecksumAlgorithm::ALL.iter() {
+            let mut hasher = Hasher::new(*algorithm);
+            hasher.update(b"hello ");
+            hasher.update(b"");
+            hasher.update(b"world");
+            assert_eq!(hasher.finish(), algorithm.checksum(b"hello world"));
+        }
+    }
+}
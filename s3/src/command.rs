@@ -1,8 +1,17 @@
 use std::collections::HashMap;
 
-use crate::serde_types::{CompleteMultipartUploadData, CorsConfiguration};
+use crate::serde_types::{
+    CompleteMultipartUploadData, CorsConfiguration, DeleteObjectsData, LifecycleConfiguration,
+    ObjectAttribute, ObjectLegalHold, ObjectLockConfiguration, ObjectRetention,
+    ReplicationConfiguration, RequestPaymentConfiguration, RestoreRequest,
+    SelectObjectContentRequest, ServerSideEncryptionConfiguration, VersioningConfiguration,
+    WebsiteConfiguration,
+};
 
+use crate::checksum::ChecksumAlgorithm;
+use crate::signing;
 use crate::EMPTY_PAYLOAD_SHA;
+use bytes::Bytes;
 use sha2::{Digest, Sha256};
 
 pub enum HttpMethod {
@@ -26,7 +35,7 @@ impl fmt::Display for HttpMethod {
         }
     }
 }
-use crate::bucket_ops::BucketConfiguration;
+use crate::bucket_ops::{Acl, BucketConfiguration};
 use http::HeaderMap;
 
 #[derive(Clone, Debug)]
@@ -58,28 +67,59 @@ pub enum Command<'a> {
         from: &'a str,
     },
     DeleteObject,
-    DeleteObjectTagging,
+    DeleteObjects {
+        data: DeleteObjectsData,
+    },
+    DeleteObjectTagging {
+        version_id: Option<&'a str>,
+    },
     GetObject,
     GetObjectTorrent,
     GetObjectRange {
         start: u64,
         end: Option<u64>,
     },
-    GetObjectTagging,
+    GetObjectTagging {
+        version_id: Option<&'a str>,
+    },
     PutObject {
-        content: &'a [u8],
+        content: Bytes,
         content_type: &'a str,
         multipart: Option<Multipart<'a>>,
     },
+    /// Single PUT whose body of `content_length` bytes is sent aws-chunked, see
+    /// `signing::ChunkSigner`, followed by a trailing `checksum` of the body if set
+    PutObjectStream {
+        content_type: &'a str,
+        content_length: u64,
+        checksum: Option<ChecksumAlgorithm>,
+    },
     PutObjectTagging {
         tags: &'a str,
+        version_id: Option<&'a str>,
     },
+    GetBucketTagging,
+    PutBucketTagging {
+        tags: &'a str,
+    },
+    DeleteBucketTagging,
     ListMultipartUploads {
         prefix: Option<&'a str>,
         delimiter: Option<&'a str>,
         key_marker: Option<String>,
+        upload_id_marker: Option<String>,
         max_uploads: Option<usize>,
     },
+    ListParts {
+        upload_id: &'a str,
+        part_number_marker: Option<u32>,
+        max_parts: Option<usize>,
+    },
+    GetObjectAttributes {
+        attributes: &'a [ObjectAttribute],
+        part_number_marker: Option<u32>,
+        max_parts: Option<usize>,
+    },
     ListObjects {
         prefix: String,
         delimiter: Option<String>,
@@ -96,6 +136,7 @@ pub enum Command<'a> {
     GetBucketLocation,
     PresignGet {
         expiry_secs: u32,
+        custom_headers: Option<HeaderMap>,
         custom_queries: Option<HashMap<String, String>>,
     },
     PresignPut {
@@ -105,13 +146,22 @@ pub enum Command<'a> {
     PresignDelete {
         expiry_secs: u32,
     },
+    PresignHead {
+        expiry_secs: u32,
+    },
     InitiateMultipartUpload {
         content_type: &'a str,
     },
     UploadPart {
         part_number: u32,
-        content: &'a [u8],
+        content: Bytes,
+        upload_id: &'a str,
+    },
+    UploadPartCopy {
+        from: &'a str,
+        part_number: u32,
         upload_id: &'a str,
+        range: (u64, u64),
     },
     AbortMultipartUpload {
         upload_id: &'a str,
@@ -124,10 +174,72 @@ pub enum Command<'a> {
         config: BucketConfiguration,
     },
     DeleteBucket,
+    HeadBucket,
     ListBuckets,
+    GetBucketCors,
     PutBucketCors {
         configuration: CorsConfiguration,
     },
+    DeleteBucketCors,
+    GetBucketLifecycle,
+    PutBucketLifecycle {
+        configuration: LifecycleConfiguration,
+    },
+    DeleteBucketLifecycle,
+    GetBucketReplication,
+    PutBucketReplication {
+        configuration: ReplicationConfiguration,
+    },
+    DeleteBucketReplication,
+    GetBucketEncryption,
+    PutBucketEncryption {
+        configuration: ServerSideEncryptionConfiguration,
+    },
+    DeleteBucketEncryption,
+    GetBucketWebsite,
+    PutBucketWebsite {
+        configuration: WebsiteConfiguration,
+    },
+    DeleteBucketWebsite,
+    SelectObjectContent {
+        request: SelectObjectContentRequest,
+    },
+    RestoreObject {
+        request: RestoreRequest,
+    },
+    GetBucketVersioning,
+    PutBucketVersioning {
+        configuration: VersioningConfiguration,
+    },
+    GetBucketRequestPayment,
+    PutBucketRequestPayment {
+        configuration: RequestPaymentConfiguration,
+    },
+    GetObjectLockConfiguration,
+    PutObjectLockConfiguration {
+        configuration: ObjectLockConfiguration,
+    },
+    GetObjectRetention,
+    PutObjectRetention {
+        retention: ObjectRetention,
+    },
+    GetObjectLegalHold,
+    PutObjectLegalHold {
+        legal_hold: ObjectLegalHold,
+    },
+    GetObjectAcl,
+    PutObjectAcl {
+        acl: Acl,
+    },
+    GetBucketAcl,
+    PutBucketAcl {
+        acl: Acl,
+    },
+    GetBucketPolicy,
+    PutBucketPolicy {
+        policy: &'a str,
+    },
+    DeleteBucketPolicy,
 }
 
 impl<'a> Command<'a> {
@@ -140,25 +252,141 @@ impl<'a> Command<'a> {
             | Command::ListObjects { .. }
             | Command::ListObjectsV2 { .. }
             | Command::GetBucketLocation
-            | Command::GetObjectTagging
+            | Command::GetObjectTagging { .. }
+            | Command::GetBucketTagging
             | Command::ListMultipartUploads { .. }
+            | Command::ListParts { .. }
+            | Command::GetObjectAttributes { .. }
+            | Command::GetBucketVersioning
+            | Command::GetBucketRequestPayment
+            | Command::GetObjectLockConfiguration
+            | Command::GetObjectRetention
+            | Command::GetObjectLegalHold
+            | Command::GetObjectAcl
+            | Command::GetBucketAcl
+            | Command::GetBucketPolicy
+            | Command::GetBucketCors
+            | Command::GetBucketLifecycle
+            | Command::GetBucketReplication
+            | Command::GetBucketEncryption
+            | Command::GetBucketWebsite
             | Command::PresignGet { .. } => HttpMethod::Get,
             Command::PutObject { .. }
+            | Command::PutObjectStream { .. }
             | Command::CopyObject { from: _ }
             | Command::PutObjectTagging { .. }
+            | Command::PutBucketTagging { .. }
             | Command::PresignPut { .. }
             | Command::UploadPart { .. }
+            | Command::UploadPartCopy { .. }
             | Command::PutBucketCors { .. }
+            | Command::PutBucketVersioning { .. }
+            | Command::PutBucketRequestPayment { .. }
+            | Command::PutObjectLockConfiguration { .. }
+            | Command::PutObjectRetention { .. }
+            | Command::PutObjectLegalHold { .. }
+            | Command::PutObjectAcl { .. }
+            | Command::PutBucketAcl { .. }
+            | Command::PutBucketPolicy { .. }
+            | Command::PutBucketLifecycle { .. }
+            | Command::PutBucketReplication { .. }
+            | Command::PutBucketEncryption { .. }
+            | Command::PutBucketWebsite { .. }
             | Command::CreateBucket { .. } => HttpMethod::Put,
             Command::DeleteObject
-            | Command::DeleteObjectTagging
+            | Command::DeleteObjectTagging { .. }
+            | Command::DeleteBucketTagging
             | Command::AbortMultipartUpload { .. }
             | Command::PresignDelete { .. }
+            | Command::DeleteBucketPolicy
+            | Command::DeleteBucketCors
+            | Command::DeleteBucketLifecycle
+            | Command::DeleteBucketReplication
+            | Command::DeleteBucketEncryption
+            | Command::DeleteBucketWebsite
             | Command::DeleteBucket => HttpMethod::Delete,
-            Command::InitiateMultipartUpload { .. } | Command::CompleteMultipartUpload { .. } => {
-                HttpMethod::Post
+            Command::InitiateMultipartUpload { .. }
+            | Command::CompleteMultipartUpload { .. }
+            | Command::DeleteObjects { .. }
+            | Command::SelectObjectContent { .. }
+            | Command::RestoreObject { .. } => HttpMethod::Post,
+            Command::HeadObject | Command::HeadBucket | Command::PresignHead { .. } => {
+                HttpMethod::Head
             }
-            Command::HeadObject => HttpMethod::Head,
+        }
+    }
+
+    /// Name of the S3 operation, `GetObject`, `PutObject`...
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Command::HeadObject => "HeadObject",
+            Command::CopyObject { .. } => "CopyObject",
+            Command::DeleteObject => "DeleteObject",
+            Command::DeleteObjects { .. } => "DeleteObjects",
+            Command::DeleteObjectTagging { .. } => "DeleteObjectTagging",
+            Command::GetObject => "GetObject",
+            Command::GetObjectTorrent => "GetObjectTorrent",
+            Command::GetObjectRange { .. } => "GetObjectRange",
+            Command::GetObjectTagging { .. } => "GetObjectTagging",
+            Command::PutObject { .. } => "PutObject",
+            Command::PutObjectStream { .. } => "PutObjectStream",
+            Command::PutObjectTagging { .. } => "PutObjectTagging",
+            Command::GetBucketTagging => "GetBucketTagging",
+            Command::PutBucketTagging { .. } => "PutBucketTagging",
+            Command::DeleteBucketTagging => "DeleteBucketTagging",
+            Command::ListMultipartUploads { .. } => "ListMultipartUploads",
+            Command::ListParts { .. } => "ListParts",
+            Command::GetObjectAttributes { .. } => "GetObjectAttributes",
+            Command::ListObjects { .. } => "ListObjects",
+            Command::ListObjectsV2 { .. } => "ListObjectsV2",
+            Command::GetBucketLocation => "GetBucketLocation",
+            Command::PresignGet { .. } => "PresignGet",
+            Command::PresignPut { .. } => "PresignPut",
+            Command::PresignDelete { .. } => "PresignDelete",
+            Command::PresignHead { .. } => "PresignHead",
+            Command::InitiateMultipartUpload { .. } => "InitiateMultipartUpload",
+            Command::UploadPart { .. } => "UploadPart",
+            Command::UploadPartCopy { .. } => "UploadPartCopy",
+            Command::AbortMultipartUpload { .. } => "AbortMultipartUpload",
+            Command::CompleteMultipartUpload { .. } => "CompleteMultipartUpload",
+            Command::CreateBucket { .. } => "CreateBucket",
+            Command::DeleteBucket => "DeleteBucket",
+            Command::HeadBucket => "HeadBucket",
+            Command::ListBuckets => "ListBuckets",
+            Command::GetBucketCors => "GetBucketCors",
+            Command::PutBucketCors { .. } => "PutBucketCors",
+            Command::DeleteBucketCors => "DeleteBucketCors",
+            Command::GetBucketLifecycle => "GetBucketLifecycle",
+            Command::PutBucketLifecycle { .. } => "PutBucketLifecycle",
+            Command::DeleteBucketLifecycle => "DeleteBucketLifecycle",
+            Command::GetBucketReplication => "GetBucketReplication",
+            Command::PutBucketReplication { .. } => "PutBucketReplication",
+            Command::DeleteBucketReplication => "DeleteBucketReplication",
+            Command::GetBucketEncryption => "GetBucketEncryption",
+            Command::PutBucketEncryption { .. } => "PutBucketEncryption",
+            Command::DeleteBucketEncryption => "DeleteBucketEncryption",
+            Command::GetBucketWebsite => "GetBucketWebsite",
+            Command::PutBucketWebsite { .. } => "PutBucketWebsite",
+            Command::DeleteBucketWebsite => "DeleteBucketWebsite",
+            Command::SelectObjectContent { .. } => "SelectObjectContent",
+            Command::RestoreObject { .. } => "RestoreObject",
+            Command::GetBucketVersioning => "GetBucketVersioning",
+            Command::PutBucketVersioning { .. } => "PutBucketVersioning",
+            Command::GetBucketRequestPayment => "GetBucketRequestPayment",
+            Command::PutBucketRequestPayment { .. } => "PutBucketRequestPayment",
+            Command::GetObjectLockConfiguration => "GetObjectLockConfiguration",
+            Command::PutObjectLockConfiguration { .. } => "PutObjectLockConfiguration",
+            Command::GetObjectRetention => "GetObjectRetention",
+            Command::PutObjectRetention { .. } => "PutObjectRetention",
+            Command::GetObjectLegalHold => "GetObjectLegalHold",
+            Command::PutObjectLegalHold { .. } => "PutObjectLegalHold",
+            Command::GetObjectAcl => "GetObjectAcl",
+            Command::PutObjectAcl { .. } => "PutObjectAcl",
+            Command::GetBucketAcl => "GetBucketAcl",
+            Command::PutBucketAcl { .. } => "PutBucketAcl",
+            Command::GetBucketPolicy => "GetBucketPolicy",
+            Command::PutBucketPolicy { .. } => "PutBucketPolicy",
+            Command::DeleteBucketPolicy => "DeleteBucketPolicy",
         }
     }
 
@@ -166,9 +394,38 @@ impl<'a> Command<'a> {
         match &self {
             Command::CopyObject { from: _ } => 0,
             Command::PutObject { content, .. } => content.len(),
-            Command::PutObjectTagging { tags } => tags.len(),
+            Command::PutObjectStream {
+                content_length,
+                checksum: None,
+                ..
+            } => signing::aws_chunked_length(*content_length) as usize,
+            Command::PutObjectStream {
+                content_length,
+                checksum: Some(algorithm),
+                ..
+            } => signing::aws_chunked_length_with_trailer(*content_length, *algorithm) as usize,
+            Command::PutObjectTagging { tags, .. } | Command::PutBucketTagging { tags } => {
+                tags.len()
+            }
             Command::UploadPart { content, .. } => content.len(),
             Command::CompleteMultipartUpload { data, .. } => data.len(),
+            Command::DeleteObjects { data } => data.len(),
+            Command::PutBucketVersioning { configuration } => configuration.len(),
+            Command::PutBucketRequestPayment { configuration } => configuration.len(),
+            Command::PutObjectLockConfiguration { configuration } => configuration.len(),
+            Command::PutObjectRetention { retention } => retention.len(),
+            Command::PutObjectLegalHold { legal_hold } => legal_hold.len(),
+            Command::PutObjectAcl { acl } | Command::PutBucketAcl { acl } => {
+                acl.body().map(|body| body.len()).unwrap_or(0)
+            }
+            Command::PutBucketPolicy { policy } => policy.len(),
+            Command::PutBucketCors { configuration } => configuration.len(),
+            Command::PutBucketLifecycle { configuration } => configuration.len(),
+            Command::PutBucketReplication { configuration } => configuration.len(),
+            Command::PutBucketEncryption { configuration } => configuration.len(),
+            Command::PutBucketWebsite { configuration } => configuration.len(),
+            Command::SelectObjectContent { request } => request.len(),
+            Command::RestoreObject { request } => request.len(),
             Command::CreateBucket { config } => {
                 if let Some(payload) = config.location_constraint_payload() {
                     Vec::from(payload).len()
@@ -183,20 +440,41 @@ impl<'a> Command<'a> {
     pub fn content_type(&self) -> String {
         match self {
             Command::InitiateMultipartUpload { content_type } => content_type.to_string(),
-            Command::PutObject { content_type, .. } => content_type.to_string(),
-            Command::CompleteMultipartUpload { .. } => "application/xml".into(),
+            Command::PutObject { content_type, .. }
+            | Command::PutObjectStream { content_type, .. } => content_type.to_string(),
+            Command::PutBucketPolicy { .. } => "application/json".into(),
+            Command::CompleteMultipartUpload { .. }
+            | Command::DeleteObjects { .. }
+            | Command::PutBucketVersioning { .. }
+            | Command::PutBucketRequestPayment { .. }
+            | Command::PutObjectLockConfiguration { .. }
+            | Command::PutObjectRetention { .. }
+            | Command::PutObjectLegalHold { .. }
+            | Command::PutObjectAcl { .. }
+            | Command::PutBucketAcl { .. }
+            | Command::PutBucketCors { .. }
+            | Command::PutBucketLifecycle { .. }
+            | Command::PutBucketReplication { .. }
+            | Command::PutBucketEncryption { .. }
+            | Command::PutBucketWebsite { .. }
+            | Command::SelectObjectContent { .. }
+            | Command::RestoreObject { .. } => "application/xml".into(),
             _ => "text/plain".into(),
         }
     }
 
     pub fn sha256(&self) -> String {
         match &self {
+            Command::PutObjectStream { checksum: None, .. } => signing::STREAMING_PAYLOAD.into(),
+            Command::PutObjectStream {
+                checksum: Some(_), ..
+            } => signing::STREAMING_PAYLOAD_TRAILER.into(),
             Command::PutObject { content, .. } => {
                 let mut sha = Sha256::default();
                 sha.update(content);
                 hex::encode(sha.finalize().as_slice())
             }
-            Command::PutObjectTagging { tags } => {
+            Command::PutObjectTagging { tags, .. } | Command::PutBucketTagging { tags } => {
                 let mut sha = Sha256::default();
                 sha.update(tags.as_bytes());
                 hex::encode(sha.finalize().as_slice())
@@ -206,6 +484,85 @@ impl<'a> Command<'a> {
                 sha.update(data.to_string().as_bytes());
                 hex::encode(sha.finalize().as_slice())
             }
+            Command::DeleteObjects { data } => {
+                let mut sha = Sha256::default();
+                sha.update(data.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutBucketVersioning { configuration } => {
+                let mut sha = Sha256::default();
+                sha.update(configuration.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutBucketRequestPayment { configuration } => {
+                let mut sha = Sha256::default();
+                sha.update(configuration.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutObjectLockConfiguration { configuration } => {
+                let mut sha = Sha256::default();
+                sha.update(configuration.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutObjectRetention { retention } => {
+                let mut sha = Sha256::default();
+                sha.update(retention.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutObjectLegalHold { legal_hold } => {
+                let mut sha = Sha256::default();
+                sha.update(legal_hold.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutBucketPolicy { policy } => {
+                let mut sha = Sha256::default();
+                sha.update(policy.as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutBucketCors { configuration } => {
+                let mut sha = Sha256::default();
+                sha.update(configuration.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutBucketLifecycle { configuration } => {
+                let mut sha = Sha256::default();
+                sha.update(configuration.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutBucketReplication { configuration } => {
+                let mut sha = Sha256::default();
+                sha.update(configuration.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutBucketEncryption { configuration } => {
+                let mut sha = Sha256::default();
+                sha.update(configuration.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutBucketWebsite { configuration } => {
+                let mut sha = Sha256::default();
+                sha.update(configuration.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::SelectObjectContent { request } => {
+                let mut sha = Sha256::default();
+                sha.update(request.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::RestoreObject { request } => {
+                let mut sha = Sha256::default();
+                sha.update(request.to_string().as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
+            Command::PutObjectAcl { acl } | Command::PutBucketAcl { acl } => {
+                if let Some(body) = acl.body() {
+                    let mut sha = Sha256::default();
+                    sha.update(body.as_bytes());
+                    hex::encode(sha.finalize().as_slice())
+                } else {
+                    EMPTY_PAYLOAD_SHA.into()
+                }
+            }
             Command::CreateBucket { config } => {
                 if let Some(payload) = config.location_constraint_payload() {
                     let mut sha = Sha256::default();
@@ -0,0 +1,430 @@
+//! Syncing a local directory with the objects under a prefix, see `Bucket::sync_up` and
+//! `Bucket::sync_down`
+//!
+//! Files are matched with objects by their path relative to the directory, which is the key
+//! relative to the prefix, `/` separated on every platform.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::error::S3Error;
+use crate::serde_types::{DeleteObjectError, ListBucketResult, Object};
+use crate::utils::etag_for_path;
+
+/// Settings of `Bucket::sync_up` and `Bucket::sync_down`
+///
+/// ```
+/// use s3::SyncOptions;
+///
+/// let options = SyncOptions::new().with_delete(true).with_concurrency(16);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyncOptions {
+    /// Files transferred at once by the async methods, `sync` transfers one at a time. 0 is
+    /// treated as 1.
+    pub concurrency: usize,
+    /// Delete the files, or objects, missing on the side being synced from.
+    pub delete: bool,
+    /// Compare files and objects of the same size by ETag instead of modification time. The
+    /// ETag is only the MD5 of objects uploaded in a single part or in parts of `CHUNK_SIZE`
+    /// and without SSE-KMS or SSE-C, other objects always compare as changed.
+    pub compare_etag: bool,
+    /// Only report what would be transferred and deleted.
+    pub dry_run: bool,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        SyncOptions {
+            concurrency: 4,
+            delete: false,
+            compare_etag: false,
+            dry_run: false,
+        }
+    }
+}
+
+impl SyncOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn with_delete(mut self, delete: bool) -> Self {
+        self.delete = delete;
+        self
+    }
+
+    pub fn with_compare_etag(mut self, compare_etag: bool) -> Self {
+        self.compare_etag = compare_etag;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+/// What `Bucket::sync_up` or `Bucket::sync_down` did, or would do with `dry_run`, by relative
+/// path in order
+#[derive(Clone, Debug, Default)]
+pub struct SyncReport {
+    /// Files uploaded or downloaded.
+    pub transferred: Vec<String>,
+    /// Objects or files deleted.
+    pub deleted: Vec<String>,
+    /// Number of files the same on both sides.
+    pub unchanged: usize,
+    /// Objects `sync_down` leaves alone, as their key isn't a path under the directory, e.g.
+    /// `a/../b` or `a//b`.
+    pub skipped: Vec<String>,
+    /// Objects `sync_up` failed to delete.
+    pub delete_errors: Vec<DeleteObjectError>,
+}
+
+/// Which side of the sync is updated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Up,
+    Down,
+}
+
+/// A regular file under the directory being synced
+#[derive(Clone, Debug)]
+pub(crate) struct LocalFile {
+    pub(crate) path: PathBuf,
+    pub(crate) size: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Files and objects to transfer and to delete, by relative path
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Plan {
+    pub(crate) transfers: Vec<String>,
+    pub(crate) deletions: Vec<String>,
+    pub(crate) unchanged: usize,
+}
+
+/// `prefix` without leading `/`, and `/` terminated unless it's empty, so it only matches
+/// whole folder names.
+pub(crate) fn dir_prefix(prefix: &str) -> String {
+    let mut prefix = prefix.trim_start_matches('/').to_string();
+    if !prefix.is_empty() && !prefix.ends_with('/') {
+        prefix.push('/');
+    }
+    prefix
+}
+
+/// Regular files under `dir`, by relative path. Symlinks to files are followed, symlinks to
+/// folders aren't.
+pub(crate) fn local_files(dir: &Path) -> Result<BTreeMap<String, LocalFile>, S3Error> {
+    let mut files = BTreeMap::new();
+    let mut dirs = vec![(dir.to_path_buf(), String::new())];
+    while let Some((dir, parent)) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().into_string().map_err(|name| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("file name {:?} isn't UTF-8", name),
+                )
+            })?;
+            let relative = format!("{}{}", parent, name);
+            let file_type = entry.file_type()?;
+            let metadata = if file_type.is_symlink() {
+                match fs::metadata(entry.path()) {
+                    Ok(metadata) if metadata.is_file() => metadata,
+                    _ => continue,
+                }
+            } else if file_type.is_dir() {
+                dirs.push((entry.path(), format!("{}/", relative)));
+                continue;
+            } else if file_type.is_file() {
+                entry.metadata()?
+            } else {
+                continue;
+            };
+            files.insert(
+                relative,
+                LocalFile {
+                    path: entry.path(),
+                    size: metadata.len(),
+                    modified: metadata.modified().ok(),
+                },
+            );
+        }
+    }
+    Ok(files)
+}
+
+/// Objects of a listing of `prefix`, by key relative to it, without the `/` terminated
+/// folder markers.
+pub(crate) fn remote_objects(
+    results: Vec<ListBucketResult>,
+    prefix: &str,
+) -> BTreeMap<String, Object> {
+    results
+        .into_iter()
+        .flat_map(|result| result.contents)
+        .filter_map(|object| {
+            let relative = object.key.strip_prefix(prefix)?.to_string();
+            if relative.is_empty() || relative.ends_with('/') {
+                return None;
+            }
+            Some((relative, object))
+        })
+        .collect()
+}
+
+/// Where the object at `relative` goes under `dir`, `None` unless every segment of it is a
+/// plain file or folder name.
+pub(crate) fn local_path(dir: &Path, relative: &str) -> Option<PathBuf> {
+    let mut path = dir.to_path_buf();
+    for segment in relative.split('/') {
+        let mut components = Path::new(segment).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => path.push(segment),
+            _ => return None,
+        }
+    }
+    Some(path)
+}
+
+/// Compare the files under the directory with the objects under the prefix.
+pub(crate) fn plan(
+    local: &BTreeMap<String, LocalFile>,
+    remote: &BTreeMap<String, Object>,
+    options: &SyncOptions,
+    direction: Direction,
+) -> Result<Plan, S3Error> {
+    let mut plan = Plan::default();
+    match direction {
+        Direction::Up => {
+            for (relative, file) in local {
+                match remote.get(relative) {
+                    Some(object) if !changed(file, object, options, direction)? => {
+                        plan.unchanged += 1
+                    }
+                    _ => plan.transfers.push(relative.clone()),
+                }
+            }
+            if options.delete {
+                plan.deletions = remote
+                    .keys()
+                    .filter(|relative| !local.contains_key(*relative))
+                    .cloned()
+                    .collect();
+            }
+        }
+        Direction::Down => {
+            for (relative, object) in remote {
+                match local.get(relative) {
+                    Some(file) if !changed(file, object, options, direction)? => {
+                        plan.unchanged += 1
+                    }
+                    _ => plan.transfers.push(relative.clone()),
+                }
+            }
+            if options.delete {
+                plan.deletions = local
+                    .keys()
+                    .filter(|relative| !remote.contains_key(*relative))
+                    .cloned()
+                    .collect();
+            }
+        }
+    }
+    Ok(plan)
+}
+
+/// Whether `file` needs to be uploaded over `object`, or `object` downloaded over `file`.
+///
+/// Sizes are compared first, then ETags or the modification times, to the second as listings
+/// have no more. Uploads are newer than the file they were made from and `sync_down` gives
+/// files the time of their object, so either compares as unchanged afterwards.
+fn changed(
+    file: &LocalFile,
+    object: &Object,
+    options: &SyncOptions,
+    direction: Direction,
+) -> Result<bool, S3Error> {
+    if file.size != object.size {
+        return Ok(true);
+    }
+    if options.compare_etag {
+        if let Some(etag) = &object.e_tag {
+            let etag = etag.trim_matches('"').to_ascii_lowercase();
+            return Ok(etag != etag_for_path(&file.path)?);
+        }
+    }
+    let file_time = file
+        .modified
+        .map(|modified| OffsetDateTime::from(modified).unix_timestamp());
+    let object_time = OffsetDateTime::parse(&object.last_modified, &Rfc3339)
+        .ok()
+        .map(|last_modified| last_modified.unix_timestamp());
+    Ok(match (file_time, object_time) {
+        (Some(file_time), Some(object_time)) => match direction {
+            Direction::Up => file_time > object_time,
+            Direction::Down => object_time > file_time,
+        },
+        _ => true,
+    })
+}
+
+/// Give the downloaded `path` the `last_modified` time of its object.
+pub(crate) fn set_modified(path: &Path, last_modified: &str) -> Result<(), S3Error> {
+    if let Ok(last_modified) = OffsetDateTime::parse(last_modified, &Rfc3339) {
+        fs::OpenOptions::new()
+            .write(true)
+            .open(path)?
+            .set_modified(last_modified.into())?;
+    }
+    Ok(())
+}
+
+/// Run `transfer` for every relative path, `concurrency` at a time, returning the paths
+/// transferred in order. Stops at the first error, dropping the transfers in flight.
+#[maybe_async::async_impl]
+pub(crate) async fn transfer_all<F, T>(
+    paths: Vec<String>,
+    concurrency: usize,
+    transfer: F,
+) -> Result<Vec<String>, S3Error>
+where
+    F: FnMut(String) -> T,
+    T: std::future::Future<Output = Result<String, S3Error>>,
+{
+    use futures::StreamExt;
+
+    let mut transfers =
+        futures::stream::iter(paths.into_iter().map(transfer)).buffer_unordered(concurrency.max(1));
+    let mut transferred = Vec::new();
+    while let Some(path) = transfers.next().await {
+        transferred.push(path?);
+    }
+    transferred.sort();
+    Ok(transferred)
+}
+
+#[maybe_async::sync_impl]
+pub(crate) fn transfer_all<F>(
+    paths: Vec<String>,
+    _concurrency: usize,
+    transfer: F,
+) -> Result<Vec<String>, S3Error>
+where
+    F: FnMut(String) -> Result<String, S3Error>,
+{
+    paths.into_iter().map(transfer).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    use super::{dir_prefix, local_path, plan, Direction, LocalFile, Plan, SyncOptions};
+    use crate::serde_types::Object;
+
+    fn file(size: u64, modified: &str) -> LocalFile {
+        let modified =
+            time::OffsetDateTime::parse(modified, &time::format_description::well_known::Rfc3339)
+                .unwrap();
+        LocalFile {
+            path: "does-not-exist".into(),
+            size,
+            modified: Some(SystemTime::from(modified) + Duration::from_millis(300)),
+        }
+    }
+
+    fn object(key: &str, size: u64, last_modified: &str) -> Object {
+        Object {
+            last_modified: last_modified.to_string(),
+            e_tag: None,
+            storage_class: None,
+            key: key.to_string(),
+            owner: None,
+            size,
+        }
+    }
+
+    #[test]
+    fn test_dir_prefix() {
+        assert_eq!(dir_prefix(""), "");
+        assert_eq!(dir_prefix("/"), "");
+        assert_eq!(dir_prefix("/backups"), "backups/");
+        assert_eq!(dir_prefix("backups/"), "backups/");
+    }
+
+    #[test]
+    fn test_with_concurrency_zero() {
+        assert_eq!(SyncOptions::new().with_concurrency(0).concurrency, 1);
+        assert_eq!(SyncOptions::new().with_concurrency(8).concurrency, 8);
+    }
+
+    #[test]
+    fn test_local_path() {
+        let dir = Path::new("backup");
+        assert_eq!(
+            local_path(dir, "a/b.txt"),
+            Some(dir.join("a").join("b.txt"))
+        );
+        assert_eq!(local_path(dir, "a/../../b"), None);
+        assert_eq!(local_path(dir, "./b"), None);
+        assert_eq!(local_path(dir, "a//b"), None);
+    }
+
+    #[test]
+    fn test_plan() {
+        let t = "2024-01-01T10:00:00.000Z";
+        let later = "2024-01-01T11:00:00.000Z";
+        let local: BTreeMap<_, _> = vec![
+            ("same".to_string(), file(1, t)),
+            ("resized".to_string(), file(2, t)),
+            ("touched".to_string(), file(1, later)),
+            ("new".to_string(), file(1, t)),
+        ]
+        .into_iter()
+        .collect();
+        let remote: BTreeMap<_, _> = vec![
+            ("same".to_string(), object("p/same", 1, t)),
+            ("resized".to_string(), object("p/resized", 1, t)),
+            ("touched".to_string(), object("p/touched", 1, t)),
+            ("gone".to_string(), object("p/gone", 1, later)),
+        ]
+        .into_iter()
+        .collect();
+
+        let options = SyncOptions::new();
+        assert_eq!(
+            plan(&local, &remote, &options, Direction::Up).unwrap(),
+            Plan {
+                transfers: vec!["new".into(), "resized".into(), "touched".into()],
+                deletions: vec![],
+                unchanged: 1,
+            }
+        );
+        let options = options.with_delete(true);
+        assert_eq!(
+            plan(&local, &remote, &options, Direction::Down).unwrap(),
+            Plan {
+                transfers: vec!["gone".into(), "resized".into()],
+                deletions: vec!["new".into()],
+                unchanged: 2,
+            }
+        );
+    }
+}
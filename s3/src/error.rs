@@ -1,5 +1,10 @@
+use std::fmt;
+
 use thiserror::Error;
 
+use crate::checksum::ChecksumAlgorithm;
+use crate::request::ResponseData;
+
 #[derive(Error, Debug)]
 #[non_exhaustive]
 pub enum S3Error {
@@ -7,8 +12,14 @@ pub enum S3Error {
     Utf8(#[from] std::str::Utf8Error),
     #[error("Max expiration for presigned URLs is one week, or 604.800 seconds, got {0} instead")]
     MaxExpiry(u32),
-    #[error("Got HTTP {0} with content '{1}'")]
-    HttpFailWithBody(u16, String),
+    #[error("Could not determine the size of copy source {0}")]
+    CopySourceSize(String),
+    #[error("Could not determine the size of object {0}")]
+    ObjectSize(String),
+    #[error("{0}")]
+    HttpFailWithBody(Box<ErrorResponse>),
+    #[error("event stream: {0}")]
+    EventStream(String),
     #[error("Http request returned a non 2** code")]
     HttpFail,
     #[error("aws-creds: {0}")]
@@ -21,15 +32,16 @@ pub enum S3Error {
     UrlParse(#[from] url::ParseError),
     #[error("io: {0}")]
     Io(#[from] std::io::Error),
-    #[cfg(feature = "with-tokio")]
     #[error("http: {0}")]
     Http(#[from] http::Error),
     #[cfg(feature = "with-tokio")]
     #[error("hyper: {0}")]
     Hyper(#[from] hyper::Error),
-    #[cfg(feature = "with-tokio")]
+    #[cfg(feature = "use-tokio-native-tls")]
     #[error("native-tls: {0}")]
     NativeTls(#[from] native_tls::Error),
+    #[error("tls: {0}")]
+    Tls(String),
     #[error("header to string: {0}")]
     HeaderToStr(#[from] http::header::ToStrError),
     #[error("from utf8: {0}")]
@@ -43,15 +55,43 @@ pub enum S3Error {
     #[cfg(feature = "with-async-std")]
     #[error("surf: {0}")]
     Surf(String),
+    #[cfg(feature = "with-wasm")]
+    #[error("fetch: {0}")]
+    Fetch(String),
+    #[cfg(feature = "with-futures")]
+    #[error("no HttpBackend set, see Bucket::with_http_backend")]
+    NoHttpBackend,
     #[cfg(feature = "sync")]
     #[error("attohttpc: {0}")]
     Atto(#[from] attohttpc::Error),
+    #[error("{0} are not supported by Cloudflare R2")]
+    R2Unsupported(&'static str),
+    #[error("region {0} has no FIPS endpoint")]
+    NoFipsEndpoint(String),
+    #[error("BucketBuilder: the {0} of the bucket is required")]
+    BucketBuilder(&'static str),
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("user metadata {0} needs a header token as key and printable ASCII as value")]
+    InvalidMetadata(String),
+    #[error("header {0} is signed, interceptors can only add headers")]
+    SignedHeader(String),
+    #[error("downloaded body has MD5 {actual}, its ETag is {expected}")]
+    EtagMismatch { expected: String, actual: String },
+    #[error("downloaded body has {algorithm} checksum {actual}, the object has {expected}")]
+    ChecksumMismatch {
+        algorithm: ChecksumAlgorithm,
+        expected: String,
+        actual: String,
+    },
     #[error("Could not get Write lock on Credentials")]
     WLCredentials,
     #[error("Could not get Read lock on Credentials")]
     RLCredentials,
     #[error("Time format error: {0}")]
     TimeFormatError(#[from] time::error::Format),
+    #[error("Time parse error: {0}")]
+    TimeParseError(#[from] time::error::Parse),
     #[error("fmt error: {0}")]
     FmtError(#[from] std::fmt::Error),
     #[error("serde error: {0}")]
@@ -63,3 +103,279 @@ pub enum S3Error {
     #[error("Could not get write lock on credentials")]
     CredentialsWriteLock,
 }
+
+impl S3Error {
+    /// The S3 error response, for requests answered with anything but 2xx. Only with
+    /// `fail-on-err`, otherwise those come back as `Ok` responses.
+    pub fn error_response(&self) -> Option<&ErrorResponse> {
+        match self {
+            S3Error::HttpFailWithBody(error_response) => Some(error_response),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the error response.
+    pub fn status_code(&self) -> Option<u16> {
+        self.error_response()
+            .map(|error_response| error_response.status_code)
+    }
+
+    /// S3 error code of the error response, `NoSuchKey`, `AccessDenied`...
+    pub fn code(&self) -> Option<&str> {
+        self.error_response()
+            .and_then(|error_response| error_response.code.as_deref())
+    }
+
+    /// The bucket, object or upload doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        self.status_code() == Some(404)
+            || matches!(
+                self.code(),
+                Some("NoSuchKey" | "NoSuchBucket" | "NoSuchUpload" | "NoSuchVersion")
+            )
+    }
+
+    /// The request was refused because of its rate, back off before sending more.
+    pub fn is_throttling(&self) -> bool {
+        matches!(self.status_code(), Some(429 | 503))
+            || matches!(
+                self.code(),
+                Some(
+                    "SlowDown"
+                        | "Throttling"
+                        | "ThrottlingException"
+                        | "RequestLimitExceeded"
+                        | "TooManyRequests"
+                )
+            )
+    }
+
+    /// The request failed on the way, the connection broke or timed out before a response.
+    pub fn is_transport_error(&self) -> bool {
+        match self {
+            S3Error::Io(_) => true,
+            #[cfg(feature = "with-tokio")]
+            S3Error::Hyper(_) => true,
+            #[cfg(feature = "with-async-std")]
+            S3Error::Surf(_) => true,
+            #[cfg(feature = "with-wasm")]
+            S3Error::Fetch(_) => true,
+            #[cfg(feature = "sync")]
+            S3Error::Atto(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Sending the same request again may succeed: transport errors, throttling, server
+    /// errors and timed out uploads. `RetryPolicy` retries these on its own when set on the
+    /// bucket, except for `EtagMismatch` and `ChecksumMismatch`, found once the body is read.
+    pub fn is_retryable(&self) -> bool {
+        self.is_transport_error()
+            || matches!(
+                self,
+                S3Error::EtagMismatch { .. } | S3Error::ChecksumMismatch { .. }
+            )
+            || self.is_throttling()
+            || matches!(self.status_code(), Some(500 | 502 | 504))
+            || matches!(self.code(), Some("InternalError" | "RequestTimeout"))
+    }
+}
+
+/// Error response of the S3 API, a request answered with anything but 2xx
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorResponse {
+    pub status_code: u16,
+    /// S3 error code, `NoSuchKey`, `AccessDenied`, `SlowDown`...
+    pub code: Option<String>,
+    pub message: Option<String>,
+    /// Bucket or object the error is about.
+    pub resource: Option<String>,
+    /// `x-amz-request-id` of the failed request, quote it in support tickets.
+    pub request_id: Option<String>,
+    /// `x-amz-id-2`, the extended request id AWS support asks for along the request id.
+    pub host_id: Option<String>,
+    /// The response body as received.
+    pub body: String,
+}
+
+#[derive(Deserialize, Default)]
+struct ErrorBody {
+    #[serde(rename = "Code")]
+    code: Option<String>,
+    #[serde(rename = "Message")]
+    message: Option<String>,
+    #[serde(rename = "Resource")]
+    resource: Option<String>,
+    #[serde(rename = "RequestId")]
+    request_id: Option<String>,
+    #[serde(rename = "HostId")]
+    host_id: Option<String>,
+}
+
+impl ErrorResponse {
+    /// Error response with status `status_code` and `body`, the fields are read from the
+    /// XML error document when there is one. HEAD responses and some proxies come without.
+    /// The ids of the response headers win over the ones of the body.
+    pub fn new(
+        status_code: u16,
+        request_id: Option<String>,
+        host_id: Option<String>,
+        body: &[u8],
+    ) -> ErrorResponse {
+        let body = String::from_utf8_lossy(body).to_string();
+        let error_body = quick_xml::de::from_str::<ErrorBody>(&body).unwrap_or_default();
+        ErrorResponse {
+            status_code,
+            code: error_body.code,
+            message: error_body.message,
+            resource: error_body.resource,
+            request_id: request_id.or(error_body.request_id),
+            host_id: host_id.or(error_body.host_id),
+            body,
+        }
+    }
+
+    pub(crate) fn from_headers(
+        status_code: u16,
+        headers: &http::HeaderMap,
+        body: &[u8],
+    ) -> ErrorResponse {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+        ErrorResponse::new(
+            status_code,
+            header("x-amz-request-id"),
+            header("x-amz-id-2"),
+            body,
+        )
+    }
+
+    pub(crate) fn from_response_data(response_data: &ResponseData) -> ErrorResponse {
+        let mut headers = response_data.headers();
+        ErrorResponse::new(
+            response_data.status_code(),
+            headers.remove("x-amz-request-id"),
+            headers.remove("x-amz-id-2"),
+            response_data.as_slice(),
+        )
+    }
+}
+
+impl fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HTTP {}", self.status_code)?;
+        match (&self.code, &self.message) {
+            (Some(code), Some(message)) => write!(f, " {}: {}", code, message)?,
+            (Some(code), None) => write!(f, " {}", code)?,
+            (None, Some(message)) => write!(f, ": {}", message)?,
+            (None, None) if !self.body.is_empty() => write!(f, ": {}", self.body)?,
+            (None, None) => {}
+        }
+        if let Some(resource) = &self.resource {
+            write!(f, " ({})", resource)?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, ", request id {}", request_id)?;
+        }
+        if let Some(host_id) = &self.host_id {
+            write!(f, ", host id {}", host_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<ErrorResponse> for S3Error {
+    fn from(error_response: ErrorResponse) -> S3Error {
+        S3Error::HttpFailWithBody(Box::new(error_response))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ErrorResponse, S3Error};
+    use crate::checksum::ChecksumAlgorithm;
+
+    #[test]
+    fn test_error_response() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>NoSuchKey</Code>
+  <Message>The resource you requested does not exist</Message>
+  <Resource>/mybucket/myfoto.jpg</Resource>
+  <RequestId>4442587FB7D0A2F9</RequestId>
+</Error>"#;
+        let error = ErrorResponse::new(404, None, Some("7Yp3...".to_string()), body.as_bytes());
+        assert_eq!(error.status_code, 404);
+        assert_eq!(error.code.as_deref(), Some("NoSuchKey"));
+        assert_eq!(
+            error.message.as_deref(),
+            Some("The resource you requested does not exist")
+        );
+        assert_eq!(error.resource.as_deref(), Some("/mybucket/myfoto.jpg"));
+        assert_eq!(error.request_id.as_deref(), Some("4442587FB7D0A2F9"));
+        assert_eq!(error.host_id.as_deref(), Some("7Yp3..."));
+        assert_eq!(
+            error.to_string(),
+            "HTTP 404 NoSuchKey: The resource you requested does not exist \
+             (/mybucket/myfoto.jpg), request id 4442587FB7D0A2F9, host id 7Yp3..."
+        );
+
+        let error = ErrorResponse::new(403, None, None, b"");
+        assert_eq!(error.code, None);
+        assert_eq!(error.body, "");
+        assert_eq!(error.to_string(), "HTTP 403");
+    }
+
+    #[test]
+    fn test_classification() {
+        let error = |status_code, code: &str| -> S3Error {
+            let body = format!("<Error><Code>{}</Code></Error>", code);
+            ErrorResponse::new(status_code, None, None, body.as_bytes()).into()
+        };
+
+        let not_found = error(404, "NoSuchKey");
+        assert_eq!(not_found.status_code(), Some(404));
+        assert_eq!(not_found.code(), Some("NoSuchKey"));
+        assert!(not_found.is_not_found());
+        assert!(!not_found.is_retryable());
+
+        let slow_down = error(503, "SlowDown");
+        assert!(slow_down.is_throttling());
+        assert!(slow_down.is_retryable());
+
+        let timeout = error(400, "RequestTimeout");
+        assert!(!timeout.is_throttling());
+        assert!(timeout.is_retryable());
+
+        let denied = error(403, "AccessDenied");
+        assert!(!denied.is_not_found());
+        assert!(!denied.is_retryable());
+
+        let io = S3Error::Io(std::io::ErrorKind::ConnectionReset.into());
+        assert_eq!(io.status_code(), None);
+        assert!(io.is_transport_error());
+        assert!(io.is_retryable());
+
+        let mismatch = S3Error::EtagMismatch {
+            expected: "5eb63bbbe01eeed093cb22bb8f5acdc3".to_string(),
+            actual: "d41d8cd98f00b204e9800998ecf8427e".to_string(),
+        };
+        assert!(!mismatch.is_transport_error());
+        assert!(mismatch.is_retryable());
+
+        let mismatch = S3Error::ChecksumMismatch {
+            algorithm: ChecksumAlgorithm::Crc32,
+            expected: "DUoRhQ==".to_string(),
+            actual: "AAAAAA==".to_string(),
+        };
+        assert!(mismatch.is_retryable());
+        assert_eq!(
+            mismatch.to_string(),
+            "downloaded body has CRC32 checksum AAAAAA==, the object has DUoRhQ=="
+        );
+    }
+}
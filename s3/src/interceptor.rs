@@ -0,0 +1,119 @@
+//! Hooks into the requests a `Bucket` sends
+
+use std::fmt;
+
+use http::header::{HeaderName, HeaderValue};
+use http::HeaderMap;
+use url::Url;
+
+use crate::command::HttpMethod;
+use crate::error::S3Error;
+
+/// Inspects the requests of a `Bucket` on their way out and observes their outcome
+///
+/// `before_send` sees every request once it's signed, before the first attempt at sending it,
+/// and may add headers that aren't signed. `on_response` is called after every attempt, retries
+/// included. Both run on the task sending the request, keep them quick.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use s3::bucket::Bucket;
+/// use s3::creds::Credentials;
+/// use s3::error::S3Error;
+/// use s3::interceptor::{InterceptedRequest, InterceptedResponse, Interceptor};
+///
+/// #[derive(Debug)]
+/// struct Audit;
+///
+/// impl Interceptor for Audit {
+///     fn before_send(&self, request: &mut InterceptedRequest<'_>) -> Result<(), S3Error> {
+///         request.insert_header(
+///             http::header::HeaderName::from_static("x-proxy-token"),
+///             http::header::HeaderValue::from_static("secret"),
+///         )
+///     }
+///
+///     fn on_response(&self, response: &InterceptedResponse<'_>) {
+///         println!(
+///             "{} {}/{} -> {:?} in {:?}",
+///             response.operation, response.bucket, response.path, response.status(), response.latency
+///         );
+///     }
+/// }
+///
+/// let bucket = Bucket::new("rust-s3-test", "us-east-1".parse().unwrap(), Credentials::default().unwrap())
+///     .unwrap()
+///     .with_interceptor(Arc::new(Audit));
+/// ```
+pub trait Interceptor: fmt::Debug + Send + Sync {
+    fn before_send(&self, _request: &mut InterceptedRequest<'_>) -> Result<(), S3Error> {
+        Ok(())
+    }
+
+    fn on_response(&self, _response: &InterceptedResponse<'_>) {}
+}
+
+/// A signed request about to be sent
+pub struct InterceptedRequest<'a> {
+    method: HttpMethod,
+    url: &'a Url,
+    headers: &'a mut HeaderMap,
+}
+
+impl<'a> InterceptedRequest<'a> {
+    pub(crate) fn new(
+        method: HttpMethod,
+        url: &'a Url,
+        headers: &'a mut HeaderMap,
+    ) -> InterceptedRequest<'a> {
+        InterceptedRequest {
+            method,
+            url,
+            headers,
+        }
+    }
+
+    pub fn method(&self) -> &HttpMethod {
+        &self.method
+    }
+
+    pub fn url(&self) -> &Url {
+        self.url
+    }
+
+    /// Headers of the request, `Authorization` included.
+    pub fn headers(&self) -> &HeaderMap {
+        self.headers
+    }
+
+    /// Send `name` along with the request. Headers already set are signed, or set for the
+    /// signature, and can't be replaced.
+    pub fn insert_header(&mut self, name: HeaderName, value: HeaderValue) -> Result<(), S3Error> {
+        if self.headers.contains_key(&name) {
+            return Err(S3Error::SignedHeader(name.to_string()));
+        }
+        self.headers.insert(name, value);
+        Ok(())
+    }
+}
+
+/// Outcome of an attempt at sending a request
+#[derive(Debug)]
+pub struct InterceptedResponse<'a> {
+    /// Name of the S3 operation, `GetObject`, `PutObject`...
+    pub operation: &'static str,
+    pub bucket: &'a str,
+    pub path: &'a str,
+    /// 1 for the first attempt, counting up with retries.
+    pub attempt: u32,
+    /// The response status, or the error the attempt failed with before a response.
+    pub outcome: Result<u16, &'a S3Error>,
+    pub latency: std::time::Duration,
+}
+
+impl InterceptedResponse<'_> {
+    /// Status of the response, `None` when there was none.
+    pub fn status(&self) -> Option<u16> {
+        self.outcome.ok()
+    }
+}
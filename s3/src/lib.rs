@@ -7,22 +7,39 @@ extern crate serde_derive;
 pub use awscreds as creds;
 pub use awsregion as region;
 
-pub use bucket::Bucket;
-pub use bucket::Tag;
+pub use bucket::{Bucket, BucketBuilder};
+pub use bucket::{
+    PutObjectOptions, RenameOptions, ResponseHeaderOverrides, SignatureVersion, Tag, TagSet,
+};
 pub use bucket_ops::BucketConfiguration;
+pub use client::S3Client;
+#[cfg(any(feature = "with-tokio", feature = "with-async-std", feature = "sync"))]
+pub use dir_sync::{SyncOptions, SyncReport};
 pub use post_policy::{PostPolicy, PostPolicyChecksum, PostPolicyField, PostPolicyValue};
 pub use region::Region;
+pub use sse::{SseCustomerKey, SseKms};
 
 pub mod bucket;
 pub mod bucket_ops;
+pub mod checksum;
+pub mod client;
 pub mod command;
 pub mod deserializer;
+#[cfg(any(feature = "with-tokio", feature = "with-async-std", feature = "sync"))]
+pub mod dir_sync;
+pub mod interceptor;
 pub mod post_policy;
+pub mod progress;
+pub mod proxy;
+pub mod rate_limit;
 pub mod serde_types;
 pub mod signing;
+pub mod sse;
+pub mod tls;
 
 pub mod error;
 pub mod request;
+pub mod retry;
 pub mod utils;
 
 const LONG_DATETIME: &[time::format_description::FormatItem<'static>] =
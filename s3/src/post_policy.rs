@@ -30,7 +30,11 @@ impl<'a> PostPolicy<'a> {
 
     /// Build a finalized post policy with credentials
     #[maybe_async::maybe_async]
-    async fn build(&self, now: &OffsetDateTime, bucket: &Bucket) -> Result<PostPolicy, S3Error> {
+    async fn build(
+        &self,
+        now: &OffsetDateTime,
+        bucket: &Bucket,
+    ) -> Result<PostPolicy<'_>, S3Error> {
         let access_key = bucket.access_key().await?.ok_or(S3Error::Credentials(
             CredentialsError::ConfigMissingAccessKeyId,
         ))?;
@@ -82,7 +86,7 @@ impl<'a> PostPolicy<'a> {
         use hmac::Mac;
 
         bucket.credentials_refresh().await?;
-        let now = now_utc();
+        let now = bucket.now();
 
         let policy = self.build(&now, &bucket).await?;
         let policy_string = policy.policy_string()?;
@@ -0,0 +1,215 @@
+//! Progress of streaming uploads and downloads
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Bytes moved so far by an upload or download
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    pub bytes_transferred: u64,
+    /// Size of the transfer, `None` when it isn't known up front.
+    pub total: Option<u64>,
+}
+
+/// Called with the `Progress` of the transfers of a `Bucket`
+///
+/// Uploads report once per part uploaded, downloads every time the writer took a chunk. The
+/// callback runs on the task doing the transfer, hand the numbers to a channel for anything
+/// slow.
+///
+/// ```no_run
+/// use s3::bucket::Bucket;
+/// use s3::creds::Credentials;
+/// use s3::progress::ProgressReporter;
+///
+/// let bucket = Bucket::new("rust-s3-test", "us-east-1".parse().unwrap(), Credentials::default().unwrap())
+///     .unwrap()
+///     .with_progress(ProgressReporter::new(|progress| {
+///         println!("{} of {:?} bytes", progress.bytes_transferred, progress.total)
+///     }));
+/// ```
+#[derive(Clone)]
+pub struct ProgressReporter(Arc<dyn Fn(Progress) + Send + Sync>);
+
+impl ProgressReporter {
+    pub fn new(report: impl Fn(Progress) + Send + Sync + 'static) -> ProgressReporter {
+        ProgressReporter(Arc::new(report))
+    }
+
+    pub(crate) fn report(&self, bytes_transferred: u64, total: Option<u64>) {
+        (self.0)(Progress {
+            bytes_transferred,
+            total,
+        })
+    }
+}
+
+impl fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressReporter")
+    }
+}
+
+/// Report the bytes of `bytes_transferred` to `reporter`, when there is one.
+pub(crate) fn report(
+    reporter: Option<&ProgressReporter>,
+    bytes_transferred: u64,
+    total: Option<u64>,
+) {
+    if let Some(reporter) = reporter {
+        reporter.report(bytes_transferred, total);
+    }
+}
+
+/// Writer reporting the bytes written through it
+pub(crate) struct ProgressWriter<'a, W: ?Sized> {
+    inner: &'a mut W,
+    reporter: Option<&'a ProgressReporter>,
+    bytes_transferred: u64,
+    total: Option<u64>,
+}
+
+impl<'a, W: ?Sized> ProgressWriter<'a, W> {
+    pub(crate) fn new(
+        inner: &'a mut W,
+        reporter: Option<&'a ProgressReporter>,
+        total: Option<u64>,
+    ) -> ProgressWriter<'a, W> {
+        ProgressWriter {
+            inner,
+            reporter,
+            bytes_transferred: 0,
+            total,
+        }
+    }
+
+    fn advance(&mut self, written: usize) {
+        if written > 0 {
+            self.bytes_transferred += written as u64;
+            report(self.reporter, self.bytes_transferred, self.total);
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<W: std::io::Write + ?Sized> std::io::Write for ProgressWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.advance(written);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "with-tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin + ?Sized> tokio::io::AsyncWrite for ProgressWriter<'_, W> {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        let poll = std::pin::Pin::new(&mut *self.inner).poll_write(cx, buf);
+        if let std::task::Poll::Ready(Ok(written)) = poll {
+            self.advance(written);
+        }
+        poll
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut *self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut *self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(any(feature = "with-async-std", feature = "with-futures"))]
+impl<W: futures_io::AsyncWrite + Unpin + ?Sized> futures_io::AsyncWrite for ProgressWriter<'_, W> {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        let poll = std::pin::Pin::new(&mut *self.inner).poll_write(cx, buf);
+        if let std::task::Poll::Ready(Ok(written)) = poll {
+            self.advance(written);
+        }
+        poll
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut *self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut *self.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::{Progress, ProgressReporter};
+
+    #[test]
+    fn test_progress_reporter() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let reporter = {
+            let reported = reported.clone();
+            ProgressReporter::new(move |progress| reported.lock().unwrap().push(progress))
+        };
+        super::report(Some(&reporter), 5, Some(10));
+        super::report(None, 7, Some(10));
+        super::report(Some(&reporter), 10, Some(10));
+        assert_eq!(
+            *reported.lock().unwrap(),
+            vec![
+                Progress {
+                    bytes_transferred: 5,
+                    total: Some(10)
+                },
+                Progress {
+                    bytes_transferred: 10,
+                    total: Some(10)
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_progress_writer() {
+        use std::io::Write;
+
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let reporter = {
+            let reported = reported.clone();
+            ProgressReporter::new(move |progress: Progress| {
+                reported.lock().unwrap().push(progress.bytes_transferred)
+            })
+        };
+        let mut output = Vec::new();
+        let mut writer = super::ProgressWriter::new(&mut output, Some(&reporter), None);
+        writer.write_all(b"hello").unwrap();
+        writer.write_all(b" world").unwrap();
+        assert_eq!(output, b"hello world");
+        assert_eq!(*reported.lock().unwrap(), vec![5, 11]);
+    }
+}
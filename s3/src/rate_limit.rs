@@ -0,0 +1,194 @@
+//! Bandwidth limits for streaming uploads and downloads
+
+use std::num::NonZeroU64;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Upload and download rates a `Bucket` keeps its streaming transfers under
+///
+/// Each direction is a token bucket refilled at `bytes_per_second`, holding up to a second
+/// worth of bytes, so a transfer may burst for a second after idling. Streaming downloads wait
+/// before handing a chunk to the writer, streaming uploads before sending a chunk or part, a
+/// multipart part of `CHUNK_SIZE` bytes waits for all of its bytes at once. Clones of the limit,
+/// and of the buckets carrying it, share the budget.
+///
+/// ```no_run
+/// use std::num::NonZeroU64;
+///
+/// use s3::bucket::Bucket;
+/// use s3::creds::Credentials;
+/// use s3::rate_limit::RateLimit;
+///
+/// let bucket = Bucket::new("rust-s3-test", "us-east-1".parse().unwrap(), Credentials::default().unwrap())
+///     .unwrap()
+///     .with_rate_limit(
+///         RateLimit::default()
+///             .with_upload(NonZeroU64::new(10 * 1024 * 1024).unwrap())
+///             .with_download(NonZeroU64::new(50 * 1024 * 1024).unwrap()),
+///     );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RateLimit {
+    upload: Option<Arc<TokenBucket>>,
+    download: Option<Arc<TokenBucket>>,
+}
+
+impl RateLimit {
+    /// Limit uploads to `bytes_per_second`.
+    pub fn with_upload(mut self, bytes_per_second: NonZeroU64) -> RateLimit {
+        self.upload = Some(Arc::new(TokenBucket::new(bytes_per_second)));
+        self
+    }
+
+    /// Limit downloads to `bytes_per_second`.
+    pub fn with_download(mut self, bytes_per_second: NonZeroU64) -> RateLimit {
+        self.download = Some(Arc::new(TokenBucket::new(bytes_per_second)));
+        self
+    }
+
+    pub fn upload(&self) -> Option<NonZeroU64> {
+        self.upload.as_ref().map(|bucket| bucket.bytes_per_second)
+    }
+
+    pub fn download(&self) -> Option<NonZeroU64> {
+        self.download.as_ref().map(|bucket| bucket.bytes_per_second)
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    bytes_per_second: NonZeroU64,
+    state: Mutex<TokenState>,
+}
+
+#[derive(Debug)]
+struct TokenState {
+    /// Negative when transfers ran ahead of the rate and owe bytes.
+    tokens: f64,
+    updated_ms: Option<f64>,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_second: NonZeroU64) -> TokenBucket {
+        TokenBucket {
+            bytes_per_second,
+            state: Mutex::new(TokenState {
+                tokens: bytes_per_second.get() as f64,
+                updated_ms: None,
+            }),
+        }
+    }
+
+    /// Take `bytes` out of the bucket at `now_ms`, returning how long to wait before moving
+    /// them.
+    fn reserve_at(&self, bytes: u64, now_ms: f64) -> Duration {
+        let rate = self.bytes_per_second.get() as f64;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(updated_ms) = state.updated_ms {
+            // The wall clock may step back, don't let it drain the bucket
+            let elapsed = (now_ms - updated_ms).max(0.0) / 1000.0;
+            state.tokens = (state.tokens + elapsed * rate).min(rate);
+        }
+        state.updated_ms = Some(now_ms);
+        state.tokens -= bytes as f64;
+        if state.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-state.tokens / rate)
+        }
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", feature = "with-wasm")))]
+fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|t| t.as_secs_f64() * 1000.0)
+        .unwrap_or_default()
+}
+
+/// `SystemTime::now` panics on `wasm32-unknown-unknown`, ask JavaScript instead
+#[cfg(all(target_arch = "wasm32", feature = "with-wasm"))]
+fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+/// Wait until `bytes` may be uploaded under `rate_limit`.
+#[maybe_async::maybe_async]
+pub(crate) async fn throttle_upload(rate_limit: Option<&RateLimit>, bytes: usize) {
+    if let Some(bucket) = rate_limit.and_then(|rate_limit| rate_limit.upload.as_ref()) {
+        sleep(bucket.reserve_at(bytes as u64, now_ms())).await;
+    }
+}
+
+/// Wait until `bytes` may be downloaded under `rate_limit`.
+#[maybe_async::maybe_async]
+pub(crate) async fn throttle_download(rate_limit: Option<&RateLimit>, bytes: usize) {
+    if let Some(bucket) = rate_limit.and_then(|rate_limit| rate_limit.download.as_ref()) {
+        sleep(bucket.reserve_at(bytes as u64, now_ms())).await;
+    }
+}
+
+#[maybe_async::maybe_async]
+async fn sleep(delay: Duration) {
+    if delay == Duration::from_secs(0) {
+        return;
+    }
+    // In blocks, `maybe_async` drops the attributes of an `.await` it strips
+    #[cfg(feature = "with-tokio")]
+    {
+        tokio::time::sleep(delay).await;
+    }
+    #[cfg(feature = "with-async-std")]
+    {
+        async_std::task::sleep(delay).await;
+    }
+    #[cfg(all(feature = "with-futures", not(feature = "with-wasm")))]
+    {
+        futures_timer::Delay::new(delay).await;
+    }
+    #[cfg(feature = "with-wasm")]
+    {
+        crate::request::wasm_backend::sleep(delay).await;
+    }
+    #[cfg(feature = "sync")]
+    std::thread::sleep(delay);
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroU64;
+    use std::time::Duration;
+
+    use super::{RateLimit, TokenBucket};
+
+    #[test]
+    fn test_token_bucket() {
+        let bucket = TokenBucket::new(NonZeroU64::new(1000).unwrap());
+        // A second worth of bytes goes out right away
+        assert_eq!(bucket.reserve_at(1000, 0.0), Duration::from_secs(0));
+        // Then the rate applies
+        assert_eq!(bucket.reserve_at(500, 0.0), Duration::from_millis(500));
+        // Owed bytes are paid back first
+        assert_eq!(bucket.reserve_at(500, 500.0), Duration::from_millis(500));
+        // Idling refills up to a second worth of bytes
+        assert_eq!(bucket.reserve_at(1000, 60_000.0), Duration::from_secs(0));
+        // Chunks larger than the bucket wait for their excess
+        assert_eq!(bucket.reserve_at(3000, 61_000.0), Duration::from_secs(2));
+        // The clock stepping back doesn't refill or drain
+        assert_eq!(bucket.reserve_at(0, 0.0), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let rate_limit = RateLimit::default().with_download(NonZeroU64::new(2048).unwrap());
+        assert_eq!(rate_limit.upload(), None);
+        assert_eq!(rate_limit.download(), NonZeroU64::new(2048));
+        // Clones share the budget
+        let clone = rate_limit.clone();
+        let download = clone.download.as_ref().unwrap();
+        assert_eq!(download.reserve_at(2048, 0.0), Duration::from_secs(0));
+        let download = rate_limit.download.as_ref().unwrap();
+        assert_eq!(download.reserve_at(1024, 0.0), Duration::from_millis(500));
+    }
+}
@@ -1,17 +1,21 @@
 use async_std::io::{ReadExt, WriteExt};
-use async_std::stream::StreamExt;
 use bytes::Bytes;
 use futures_io::AsyncWrite;
-use futures_util::FutureExt;
 use std::collections::HashMap;
+use std::future::Future;
 
 use crate::bucket::Bucket;
 use crate::command::Command;
-use crate::error::S3Error;
-use crate::utils::now_utc;
+use crate::error::{ErrorResponse, S3Error};
+use crate::rate_limit::throttle_download;
 use time::OffsetDateTime;
 
 use crate::command::HttpMethod;
+use crate::request::etag_check::{verify_response, verify_stream, EtagCheck};
+use crate::request::request_trait::{
+    correct_clock_skew, redirect_bucket, skewed_server_time, BUCKET_REGION,
+};
+use crate::request::trace::AttemptSpan;
 use crate::request::{Request, ResponseData, ResponseDataStream};
 
 use http::HeaderMap;
@@ -19,6 +23,17 @@ use maybe_async::maybe_async;
 use surf::http::headers::{HeaderName, HeaderValue};
 use surf::http::Method;
 
+/// Size of the buffer used when reading a response body into a stream
+const STREAM_CHUNK_SIZE: usize = 65_536;
+
+/// A timeout as IO error, so a `RetryPolicy` retries it like other transport errors
+fn timed_out(timeout: std::time::Duration) -> S3Error {
+    S3Error::Io(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        format!("no response within {:?}", timeout),
+    ))
+}
+
 // Temporary structure for making a request
 pub struct SurfRequest<'a> {
     pub bucket: &'a Bucket,
@@ -26,6 +41,8 @@ pub struct SurfRequest<'a> {
     pub command: Command<'a>,
     pub datetime: OffsetDateTime,
     pub sync: bool,
+    /// Sent again after a region redirect or a clock skew correction, which happens once
+    pub retried: bool,
 }
 
 #[maybe_async]
@@ -37,105 +54,159 @@ impl<'a> Request for SurfRequest<'a> {
         self.datetime
     }
 
-    fn bucket(&self) -> Bucket {
-        self.bucket.clone()
+    fn bucket(&self) -> &Bucket {
+        self.bucket
     }
 
-    fn command(&self) -> Command {
-        self.command.clone()
+    fn command(&self) -> &Command<'_> {
+        &self.command
     }
 
     fn path(&self) -> String {
         self.path.to_string()
     }
 
+    fn retried(&self) -> bool {
+        self.retried
+    }
+
     async fn response(&self) -> Result<surf::Response, S3Error> {
         // Build headers
         let headers = self.headers().await?;
+        let body = self.request_body();
+        let url = self.url()?;
+
+        let retry_policy = self.bucket.retry_policy();
+        let mut attempt = 1;
+        let mut response = loop {
+            let request = match self.command.http_verb() {
+                HttpMethod::Get => surf::Request::builder(Method::Get, url.clone()),
+                HttpMethod::Delete => surf::Request::builder(Method::Delete, url.clone()),
+                HttpMethod::Put => surf::Request::builder(Method::Put, url.clone()),
+                HttpMethod::Post => surf::Request::builder(Method::Post, url.clone()),
+                HttpMethod::Head => surf::Request::builder(Method::Head, url.clone()),
+            };
 
-        let request = match self.command.http_verb() {
-            HttpMethod::Get => surf::Request::builder(Method::Get, self.url()?),
-            HttpMethod::Delete => surf::Request::builder(Method::Delete, self.url()?),
-            HttpMethod::Put => surf::Request::builder(Method::Put, self.url()?),
-            HttpMethod::Post => surf::Request::builder(Method::Post, self.url()?),
-            HttpMethod::Head => surf::Request::builder(Method::Head, self.url()?),
-        };
+            let mut request = request.body(body.to_vec());
 
-        let mut request = request.body(self.request_body());
+            for (name, value) in headers.iter() {
+                request = request.header(
+                    HeaderName::from_bytes(AsRef::<[u8]>::as_ref(&name).to_vec())
+                        .map_err(|e| S3Error::Surf(e.to_string()))?,
+                    HeaderValue::from_bytes(AsRef::<[u8]>::as_ref(&value).to_vec())
+                        .map_err(|e| S3Error::Surf(e.to_string()))?,
+                );
+            }
 
-        for (name, value) in headers.iter() {
-            request = request.header(
-                HeaderName::from_bytes(AsRef::<[u8]>::as_ref(&name).to_vec())
-                    .expect("Could not parse heaeder name"),
-                HeaderValue::from_bytes(AsRef::<[u8]>::as_ref(&value).to_vec())
-                    .expect("Could not parse header value"),
-            );
-        }
+            let span = AttemptSpan::start(self, attempt);
+            let response = match self.bucket.read_timeout() {
+                Some(read_timeout) => {
+                    match async_std::future::timeout(read_timeout, request.send()).await {
+                        Ok(response) => response.map_err(|e| S3Error::Surf(e.to_string())),
+                        Err(_) => Err(timed_out(read_timeout)),
+                    }
+                }
+                None => request
+                    .send()
+                    .await
+                    .map_err(|e| S3Error::Surf(e.to_string())),
+            };
+            span.finish(response.as_ref().map(|response| response.status().into()));
+            let delay = match &response {
+                Ok(response) => retry_policy.retry_status(
+                    attempt,
+                    response.status().into(),
+                    response
+                        .header("Retry-After")
+                        .map(|retry_after| retry_after.as_str()),
+                ),
+                Err(e) => retry_policy.retry_error(attempt, e),
+            };
+            match delay {
+                Some(delay) => async_std::task::sleep(delay).await,
+                None => break response?,
+            }
+            attempt += 1;
+        };
 
-        let response = request
-            .send()
-            .await
-            .map_err(|e| S3Error::Surf(e.to_string()))?;
+        let bucket_region = response
+            .header(BUCKET_REGION)
+            .map(|region| region.as_str().to_string());
+        let status = response.status().into();
+        if let Some(bucket) =
+            redirect_bucket(self.bucket, status, bucket_region.as_deref()).filter(|_| !self.retried)
+        {
+            let mut request = SurfRequest::new(&bucket, self.path, self.command.clone()).await?;
+            request.retried = true;
+            return request.response().await;
+        }
+        let date = response.header("Date").map(|date| date.as_str());
+        if let Some(server_time) =
+            skewed_server_time(status, date, self.datetime).filter(|_| !self.retried)
+        {
+            let body = response
+                .body_bytes()
+                .await
+                .map_err(|e| S3Error::Surf(e.to_string()))?;
+            if correct_clock_skew(self.bucket, server_time, &body) {
+                let mut request =
+                    SurfRequest::new(self.bucket, self.path, self.command.clone()).await?;
+                request.retried = true;
+                return request.response().await;
+            }
+            response.set_body(body);
+        }
 
         if cfg!(feature = "fail-on-err") && !response.status().is_success() {
-            return Err(S3Error::HttpFail);
+            let header = |name: &str| {
+                response
+                    .header(name)
+                    .map(|value| value.as_str().to_string())
+            };
+            let request_id = header("x-amz-request-id");
+            let host_id = header("x-amz-id-2");
+            let body = response
+                .body_bytes()
+                .await
+                .map_err(|e| S3Error::Surf(e.to_string()))?;
+            return Err(
+                ErrorResponse::new(response.status().into(), request_id, host_id, &body).into(),
+            );
         }
 
         Ok(response)
     }
 
     async fn response_data(&self, etag: bool) -> Result<ResponseData, S3Error> {
-        let mut response = self.response().await?;
-        let status_code = response.status();
-
-        let response_headers = response
-            .header_names()
-            .zip(response.header_values())
-            .map(|(k, v)| (k.to_string(), v.to_string()))
-            .collect::<HashMap<String, String>>();
-
-        let body_vec = if etag {
-            if let Some(etag) = response.header("ETag") {
-                Bytes::from(etag.as_str().to_string())
-            } else {
-                Bytes::from("")
-            }
-        } else {
-            let body = match response.body_bytes().await {
-                Ok(bytes) => Ok(Bytes::from(bytes)),
-                Err(e) => Err(S3Error::Surf(e.to_string())),
-            };
-            body?
-        };
-        Ok(ResponseData::new(
-            body_vec,
-            status_code.into(),
-            response_headers,
-        ))
+        if let Some(backend) = self.bucket.http_backend() {
+            return self.backend_response_data(backend.as_ref(), etag).await;
+        }
+        self.within_request_timeout(self.response_data_unbounded(etag))
+            .await
     }
 
     async fn response_data_to_writer<T: AsyncWrite + Send + Unpin>(
         &self,
         writer: &mut T,
     ) -> Result<u16, S3Error> {
-        let mut buffer = Vec::new();
-
-        let response = self.response().await?;
-
-        let status_code = response.status();
-
-        let mut stream = surf::http::Body::from_reader(response, None);
-
-        stream.read_to_end(&mut buffer).await?;
-
-        writer.write_all(&buffer).await?;
-
-        Ok(status_code.into())
+        if let Some(backend) = self.bucket.http_backend() {
+            let response = self.backend_response(backend.as_ref()).await?;
+            verify_response(self.bucket, &self.command, &response)?;
+            throttle_download(self.bucket.rate_limit(), response.body().len()).await;
+            writer.write_all(response.body()).await?;
+            return Ok(response.status().as_u16());
+        }
+        self.within_request_timeout(self.response_data_to_writer_unbounded(writer))
+            .await
     }
 
     async fn response_header(&self) -> Result<(HeaderMap, u16), S3Error> {
+        if let Some(backend) = self.bucket.http_backend() {
+            let response = self.backend_response(backend.as_ref()).await?;
+            return Ok((response.headers().clone(), response.status().as_u16()));
+        }
         let mut header_map = HeaderMap::new();
-        let response = self.response().await?;
+        let response = self.within_request_timeout(self.response()).await?;
         let status_code = response.status();
 
         for (name, value) in response.iter() {
@@ -150,22 +221,26 @@ impl<'a> Request for SurfRequest<'a> {
     }
 
     async fn response_data_to_stream(&self) -> Result<ResponseDataStream, S3Error> {
-        let mut response = self.response().await?;
+        if let Some(backend) = self.bucket.http_backend() {
+            return self.backend_response_data_to_stream(backend.as_ref()).await;
+        }
+        // The body is read at the pace of the caller
+        let mut response = self.within_request_timeout(self.response()).await?;
         let status_code = response.status();
+        let check = self.etag_check(&response);
 
-        let body = response
-            .take_body()
-            .bytes()
-            .filter_map(|n| n.ok())
-            .fold(vec![], |mut b, n| {
-                b.push(n);
-                b
-            })
-            .then(|b| async move { Ok(Bytes::from(b)) })
-            .into_stream();
+        let body = futures_util::stream::try_unfold(response.take_body(), |mut body| async move {
+            let mut chunk = vec![0; STREAM_CHUNK_SIZE];
+            let read = body.read(&mut chunk).await?;
+            if read == 0 {
+                return Ok(None);
+            }
+            chunk.truncate(read);
+            Ok(Some((Bytes::from(chunk), body)))
+        });
 
         Ok(ResponseDataStream {
-            bytes: Box::pin(body),
+            bytes: verify_stream(Box::pin(body), check),
             status_code: status_code.into(),
         })
     }
@@ -182,10 +257,99 @@ impl<'a> SurfRequest<'a> {
             bucket,
             path,
             command,
-            datetime: now_utc(),
+            datetime: bucket.now(),
             sync: false,
+            retried: false,
         })
     }
+
+    /// `future`, failing with a timeout after the bucket's `request_timeout`, which bounds
+    /// sending a request, its retries included, and reading the response.
+    async fn within_request_timeout<T>(
+        &self,
+        future: impl Future<Output = Result<T, S3Error>>,
+    ) -> Result<T, S3Error> {
+        match self.bucket.request_timeout() {
+            Some(request_timeout) => async_std::future::timeout(request_timeout, future)
+                .await
+                .unwrap_or_else(|_| Err(timed_out(request_timeout))),
+            None => future.await,
+        }
+    }
+
+    /// `response_data` without the `request_timeout`
+    async fn response_data_unbounded(&self, etag: bool) -> Result<ResponseData, S3Error> {
+        let mut response = self.response().await?;
+        let status_code = response.status();
+
+        let response_headers = response
+            .header_names()
+            .zip(response.header_values())
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<String, String>>();
+
+        let body_vec = if etag {
+            if let Some(etag) = response.header("ETag") {
+                Bytes::from(etag.as_str().to_string())
+            } else {
+                Bytes::from("")
+            }
+        } else {
+            let body = match response.body_bytes().await {
+                Ok(bytes) => Ok(Bytes::from(bytes)),
+                Err(e) => Err(S3Error::Surf(e.to_string())),
+            };
+            body?
+        };
+        Ok(ResponseData::new(
+            body_vec,
+            status_code.into(),
+            response_headers,
+        ))
+    }
+
+    /// `response_data_to_writer` without the `request_timeout`
+    async fn response_data_to_writer_unbounded<T: AsyncWrite + Send + Unpin>(
+        &self,
+        writer: &mut T,
+    ) -> Result<u16, S3Error> {
+        let mut response = self.response().await?;
+
+        let status_code = response.status();
+        let mut check = self.etag_check(&response);
+
+        let mut body = response.take_body();
+        let mut chunk = vec![0; STREAM_CHUNK_SIZE];
+        loop {
+            let read = body.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            throttle_download(self.bucket.rate_limit(), read).await;
+            writer.write_all(&chunk[..read]).await?;
+            if let Some(check) = &mut check {
+                check.update(&chunk[..read]);
+            }
+        }
+        if let Some(check) = check {
+            check.finish()?;
+        }
+
+        Ok(status_code.into())
+    }
+
+    fn etag_check(&self, response: &surf::Response) -> Option<EtagCheck> {
+        EtagCheck::for_response(
+            self.bucket,
+            &self.command,
+            response.status().into(),
+            |name| {
+                response
+                    .header(name)
+                    .map(|value| value.as_str().to_string())
+            },
+        )
+    }
 }
 
 #[cfg(test)]
@@ -1,22 +1,31 @@
 extern crate base64;
 extern crate md5;
 
-use std::io;
-use std::io::Write;
+use std::io::{Read, Write};
 
 use attohttpc::header::HeaderName;
 
 use crate::bucket::Bucket;
 use crate::command::Command;
-use crate::error::S3Error;
-use crate::utils::now_utc;
+use crate::error::{ErrorResponse, S3Error};
+use crate::proxy::Proxy;
+use crate::rate_limit::throttle_download;
+use crate::tls::TlsConfig;
 use bytes::Bytes;
 use std::collections::HashMap;
 use time::OffsetDateTime;
 
 use crate::command::HttpMethod;
+use crate::request::etag_check::{verify_response, EtagCheck};
+use crate::request::request_trait::{
+    correct_clock_skew, redirect_bucket, skewed_server_time, BUCKET_REGION,
+};
+use crate::request::trace::AttemptSpan;
 use crate::request::{Request, ResponseData};
 
+/// Size of the buffer used when copying a response body to a writer
+const STREAM_CHUNK_SIZE: usize = 65_536;
+
 // Temporary structure for making a request
 pub struct AttoRequest<'a> {
     pub bucket: &'a Bucket,
@@ -24,6 +33,8 @@ pub struct AttoRequest<'a> {
     pub command: Command<'a>,
     pub datetime: OffsetDateTime,
     pub sync: bool,
+    /// Sent again after a region redirect or a clock skew correction, which happens once
+    pub retried: bool,
 }
 
 impl<'a> Request for AttoRequest<'a> {
@@ -34,55 +45,118 @@ impl<'a> Request for AttoRequest<'a> {
         self.datetime
     }
 
-    fn bucket(&self) -> Bucket {
-        self.bucket.clone()
+    fn bucket(&self) -> &Bucket {
+        self.bucket
     }
 
-    fn command(&self) -> Command {
-        self.command.clone()
+    fn command(&self) -> &Command<'_> {
+        &self.command
     }
 
     fn path(&self) -> String {
         self.path.to_string()
     }
 
+    fn retried(&self) -> bool {
+        self.retried
+    }
+
     fn response(&self) -> Result<Self::Response, S3Error> {
         // Build headers
-        let headers = match self.headers() {
-            Ok(headers) => headers,
-            Err(e) => return Err(e),
-        };
+        let headers = self.headers()?;
+
+        let session = self.bucket.http_session();
+
+        let body = self.request_body();
+        let url = self.url()?;
+        let retry_policy = self.bucket.retry_policy();
+        let mut attempt = 1;
+        let response = loop {
+            let mut request = match self.command.http_verb() {
+                HttpMethod::Get => session.get(url.as_str()),
+                HttpMethod::Delete => session.delete(url.as_str()),
+                HttpMethod::Put => session.put(url.as_str()),
+                HttpMethod::Post => session.post(url.as_str()),
+                HttpMethod::Head => session.head(url.as_str()),
+            };
+            for (name, value) in headers.iter() {
+                request = request.header(HeaderName::from_bytes(name.as_ref())?, value);
+            }
+            if let Some(timeout) = self.bucket.request_timeout {
+                request = request.timeout(timeout);
+            }
+            if let Some(timeout) = self.bucket.connect_timeout() {
+                request = request.connect_timeout(timeout);
+            }
+            if let Some(timeout) = self.bucket.read_timeout() {
+                request = request.read_timeout(timeout);
+            }
 
-        let mut session = attohttpc::Session::new();
+            let span = AttemptSpan::start(self, attempt);
+            let response = request.bytes(&body).send().map_err(S3Error::from);
+            span.finish(response.as_ref().map(|response| response.status().as_u16()));
+            let delay = match &response {
+                Ok(response) => retry_policy.retry_status(
+                    attempt,
+                    response.status().as_u16(),
+                    response
+                        .headers()
+                        .get(attohttpc::header::RETRY_AFTER)
+                        .and_then(|retry_after| retry_after.to_str().ok()),
+                ),
+                Err(e) => retry_policy.retry_error(attempt, e),
+            };
+            match delay {
+                Some(delay) => std::thread::sleep(delay),
+                None => break response?,
+            }
+            attempt += 1;
+        };
 
-        for (name, value) in headers.iter() {
-            session.header(HeaderName::from_bytes(name.as_ref())?, value);
+        let bucket_region = response
+            .headers()
+            .get(BUCKET_REGION)
+            .and_then(|region| region.to_str().ok());
+        let status = response.status().as_u16();
+        if let Some(bucket) =
+            redirect_bucket(self.bucket, status, bucket_region).filter(|_| !self.retried)
+        {
+            let mut request = AttoRequest::new(&bucket, self.path, self.command.clone())?;
+            request.retried = true;
+            return request.response();
         }
-
-        if let Some(timeout) = self.bucket.request_timeout {
-            session.timeout(timeout)
+        let date = response
+            .headers()
+            .get(attohttpc::header::DATE)
+            .and_then(|date| date.to_str().ok());
+        if let Some(server_time) =
+            skewed_server_time(status, date, self.datetime).filter(|_| !self.retried)
+        {
+            let headers = response.headers().clone();
+            let body = response.bytes()?;
+            if correct_clock_skew(self.bucket, server_time, &body) {
+                let mut request = AttoRequest::new(self.bucket, self.path, self.command.clone())?;
+                request.retried = true;
+                return request.response();
+            }
+            // attohttpc has no response to hand back with the body read, the 403 is an error
+            return Err(ErrorResponse::from_headers(status, &headers, &body).into());
         }
 
-        let request = match self.command.http_verb() {
-            HttpMethod::Get => session.get(self.url()?),
-            HttpMethod::Delete => session.delete(self.url()?),
-            HttpMethod::Put => session.put(self.url()?),
-            HttpMethod::Post => session.post(self.url()?),
-            HttpMethod::Head => session.head(self.url()?),
-        };
-
-        let response = request.bytes(&self.request_body()).send()?;
-
         if cfg!(feature = "fail-on-err") && !response.status().is_success() {
             let status = response.status().as_u16();
-            let text = response.text()?;
-            return Err(S3Error::HttpFailWithBody(status, text));
+            let headers = response.headers().clone();
+            let body = response.bytes()?;
+            return Err(ErrorResponse::from_headers(status, &headers, &body).into());
         }
 
         Ok(response)
     }
 
     fn response_data(&self, etag: bool) -> Result<ResponseData, S3Error> {
+        if let Some(backend) = self.bucket.http_backend() {
+            return self.backend_response_data(backend.as_ref(), etag);
+        }
         let response = self.response()?;
         let status_code = response.status().as_u16();
 
@@ -112,15 +186,46 @@ impl<'a> Request for AttoRequest<'a> {
     }
 
     fn response_data_to_writer<T: Write>(&self, writer: &mut T) -> Result<u16, S3Error> {
+        if let Some(backend) = self.bucket.http_backend() {
+            let response = self.backend_response(backend.as_ref())?;
+            verify_response(self.bucket, &self.command, &response)?;
+            throttle_download(self.bucket.rate_limit(), response.body().len());
+            writer.write_all(response.body())?;
+            return Ok(response.status().as_u16());
+        }
         let mut response = self.response()?;
 
         let status_code = response.status();
-        io::copy(&mut response, writer)?;
+        let mut check = EtagCheck::for_header_map(
+            self.bucket,
+            &self.command,
+            status_code.as_u16(),
+            response.headers(),
+        );
+        let mut chunk = vec![0; STREAM_CHUNK_SIZE];
+        loop {
+            let read = response.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            throttle_download(self.bucket.rate_limit(), read);
+            writer.write_all(&chunk[..read])?;
+            if let Some(check) = &mut check {
+                check.update(&chunk[..read]);
+            }
+        }
+        if let Some(check) = check {
+            check.finish()?;
+        }
 
         Ok(status_code.as_u16())
     }
 
     fn response_header(&self) -> Result<(Self::HeaderMap, u16), S3Error> {
+        if let Some(backend) = self.bucket.http_backend() {
+            let response = self.backend_response(backend.as_ref())?;
+            return Ok((response.headers().clone(), response.status().as_u16()));
+        }
         let response = self.response()?;
         let status_code = response.status().as_u16();
         let headers = response.headers().clone();
@@ -128,6 +233,83 @@ impl<'a> Request for AttoRequest<'a> {
     }
 }
 
+/// Session shared by the requests of a `Bucket`, with its proxy and TLS settings
+///
+/// attohttpc closes the connection after every response, the session spares parsing
+/// certificates and proxy settings again per request. Headers and timeouts are set on each
+/// request.
+pub fn session(
+    proxy: Option<&Proxy>,
+    tls_config: Option<&TlsConfig>,
+) -> Result<attohttpc::Session, S3Error> {
+    let mut session = attohttpc::Session::new();
+    if let Some(proxy) = proxy {
+        let mut settings = attohttpc::ProxySettings::builder()
+            .http_proxy(proxy.http.clone())
+            .https_proxy(proxy.https.clone());
+        for host in &proxy.no_proxy {
+            settings = settings.add_no_proxy_host(host);
+        }
+        session.proxy_settings(settings.build());
+    }
+    apply_tls_config(&mut session, tls_config)?;
+    Ok(session)
+}
+
+#[cfg(any(
+    feature = "sync-native-tls",
+    feature = "sync-native-tls-vendored",
+    feature = "sync-rustls-tls"
+))]
+fn apply_tls_config(
+    session: &mut attohttpc::Session,
+    tls_config: Option<&TlsConfig>,
+) -> Result<(), S3Error> {
+    if TlsConfig::accepts_invalid_certs(tls_config) {
+        session.danger_accept_invalid_certs(true);
+        session.danger_accept_invalid_hostnames(true);
+    }
+    for pem in tls_config
+        .iter()
+        .flat_map(|config| &config.root_certificates)
+    {
+        add_root_certificate(session, pem)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sync-rustls-tls")]
+fn add_root_certificate(session: &mut attohttpc::Session, pem: &[u8]) -> Result<(), S3Error> {
+    for block in crate::tls::pem_blocks(pem)? {
+        session.add_root_certificate(rustls::Certificate(block.der));
+    }
+    Ok(())
+}
+
+#[cfg(all(
+    any(feature = "sync-native-tls", feature = "sync-native-tls-vendored"),
+    not(feature = "sync-rustls-tls")
+))]
+fn add_root_certificate(session: &mut attohttpc::Session, pem: &[u8]) -> Result<(), S3Error> {
+    let certificate =
+        native_tls::Certificate::from_pem(pem).map_err(|e| S3Error::Tls(e.to_string()))?;
+    session.add_root_certificate(certificate);
+    Ok(())
+}
+
+/// Built without TLS, there are no certificates to verify
+#[cfg(not(any(
+    feature = "sync-native-tls",
+    feature = "sync-native-tls-vendored",
+    feature = "sync-rustls-tls"
+)))]
+fn apply_tls_config(
+    _session: &mut attohttpc::Session,
+    _tls_config: Option<&TlsConfig>,
+) -> Result<(), S3Error> {
+    Ok(())
+}
+
 impl<'a> AttoRequest<'a> {
     pub fn new<'b>(
         bucket: &'b Bucket,
@@ -139,8 +321,9 @@ impl<'a> AttoRequest<'a> {
             bucket,
             path,
             command,
-            datetime: now_utc(),
+            datetime: bucket.now(),
             sync: false,
+            retried: false,
         })
     }
 }
@@ -0,0 +1,113 @@
+//! Runtime-agnostic requests, sent by the bucket's `HttpBackend`
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::io::AsyncWriteExt;
+use maybe_async::maybe_async;
+use time::OffsetDateTime;
+
+use super::etag_check::verify_response;
+use super::request_trait::{Request, ResponseData, ResponseDataStream};
+use crate::bucket::Bucket;
+use crate::command::Command;
+use crate::error::S3Error;
+use crate::rate_limit::throttle_download;
+use crate::request::HttpBackend;
+
+// Temporary structure for making a request
+pub struct BackendRequest<'a> {
+    pub bucket: &'a Bucket,
+    pub path: &'a str,
+    pub command: Command<'a>,
+    pub datetime: OffsetDateTime,
+    pub sync: bool,
+    /// Sent again after a region redirect or a clock skew correction, which happens once
+    pub retried: bool,
+}
+
+#[maybe_async]
+impl<'a> Request for BackendRequest<'a> {
+    type Response = http::Response<Bytes>;
+    type HeaderMap = http::HeaderMap;
+
+    async fn response(&self) -> Result<http::Response<Bytes>, S3Error> {
+        self.backend_response(self.http_backend()?.as_ref()).await
+    }
+
+    async fn response_data(&self, etag: bool) -> Result<ResponseData, S3Error> {
+        self.backend_response_data(self.http_backend()?.as_ref(), etag)
+            .await
+    }
+
+    async fn response_data_to_writer<T: futures_io::AsyncWrite + Send + Unpin>(
+        &self,
+        writer: &mut T,
+    ) -> Result<u16, S3Error> {
+        let response = self.response().await?;
+        verify_response(self.bucket, &self.command, &response)?;
+        throttle_download(self.bucket.rate_limit(), response.body().len()).await;
+        writer.write_all(response.body()).await?;
+        writer.flush().await?;
+        Ok(response.status().as_u16())
+    }
+
+    async fn response_data_to_stream(&self) -> Result<ResponseDataStream, S3Error> {
+        self.backend_response_data_to_stream(self.http_backend()?.as_ref())
+            .await
+    }
+
+    async fn response_header(&self) -> Result<(http::HeaderMap, u16), S3Error> {
+        let response = self.response().await?;
+        Ok((response.headers().clone(), response.status().as_u16()))
+    }
+
+    fn datetime(&self) -> OffsetDateTime {
+        self.datetime
+    }
+
+    fn bucket(&self) -> &Bucket {
+        self.bucket
+    }
+
+    fn command(&self) -> &Command<'_> {
+        &self.command
+    }
+
+    fn path(&self) -> String {
+        self.path.to_string()
+    }
+
+    fn retried(&self) -> bool {
+        self.retried
+    }
+}
+
+impl<'a> BackendRequest<'a> {
+    pub async fn new(
+        bucket: &'a Bucket,
+        path: &'a str,
+        command: Command<'a>,
+    ) -> Result<BackendRequest<'a>, S3Error> {
+        bucket.credentials_refresh().await?;
+        Ok(Self {
+            bucket,
+            path,
+            command,
+            datetime: bucket.now(),
+            sync: false,
+            retried: false,
+        })
+    }
+
+    /// The bucket's own `HttpBackend`, `FetchBackend` by default with `with-wasm`.
+    fn http_backend(&self) -> Result<Arc<dyn HttpBackend>, S3Error> {
+        match self.bucket.http_backend() {
+            Some(backend) => Ok(backend),
+            #[cfg(feature = "with-wasm")]
+            None => Ok(Arc::new(crate::request::wasm_backend::FetchBackend)),
+            #[cfg(not(feature = "with-wasm"))]
+            None => Err(S3Error::NoHttpBackend),
+        }
+    }
+}
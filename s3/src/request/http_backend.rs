@@ -0,0 +1,48 @@
+use std::fmt;
+
+use bytes::Bytes;
+
+use crate::error::S3Error;
+
+/// Sends the signed requests of a `Bucket` in place of the built-in HTTP client
+///
+/// Requests arrive fully signed with a buffered body, the backend only has to move them over
+/// the wire and hand back the buffered response. Status codes are checked, and failed requests
+/// retried according to the `RetryPolicy`, by the caller. Uploads carry `Expect: 100-continue`
+/// when the bucket opts in with `Bucket::with_expect_continue`, the client has to wait for the
+/// `100 Continue` then, and hand back the final response.
+///
+/// The trait is `async` with the `with-tokio`, `with-async-std` or `with-futures` features and
+/// blocking with `sync`, implement it with `#[async_trait::async_trait]` for the former. Handy
+/// for test doubles, or to bring an HTTP client the crate has no backend for. `with-futures`
+/// has no client of its own and sends everything through the backend.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use bytes::Bytes;
+/// use s3::bucket::Bucket;
+/// use s3::creds::Credentials;
+/// use s3::error::S3Error;
+/// use s3::request::HttpBackend;
+///
+/// #[derive(Debug)]
+/// struct NotFound;
+///
+/// #[async_trait::async_trait]
+/// impl HttpBackend for NotFound {
+///     async fn send(
+///         &self,
+///         _request: http::Request<Bytes>,
+///     ) -> Result<http::Response<Bytes>, S3Error> {
+///         Ok(http::Response::builder().status(404).body(Bytes::new())?)
+///     }
+/// }
+///
+/// let bucket = Bucket::new("rust-s3-test", "us-east-1".parse().unwrap(), Credentials::anonymous().unwrap())
+///     .unwrap()
+///     .with_http_backend(Arc::new(NotFound));
+/// ```
+#[maybe_async::maybe_async]
+pub trait HttpBackend: fmt::Debug + Send + Sync {
+    async fn send(&self, request: http::Request<Bytes>) -> Result<http::Response<Bytes>, S3Error>;
+}
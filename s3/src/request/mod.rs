@@ -2,8 +2,20 @@
 pub mod async_std_backend;
 #[cfg(feature = "sync")]
 pub mod blocking;
+pub(crate) mod etag_check;
+#[cfg(feature = "with-futures")]
+pub mod futures_backend;
+pub mod http_backend;
+#[cfg(feature = "test-utils")]
+pub mod memory_backend;
+#[cfg(feature = "with-tokio")]
+pub mod proxy_connector;
 pub mod request_trait;
 #[cfg(feature = "with-tokio")]
 pub mod tokio_backend;
+mod trace;
+#[cfg(feature = "with-wasm")]
+pub mod wasm_backend;
 
+pub use http_backend::HttpBackend;
 pub use request_trait::*;
@@ -0,0 +1,196 @@
+//! hyper connector sending requests through a `Proxy`
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use hyper::client::connect::{Connected, Connection};
+use hyper::client::HttpConnector;
+use hyper::service::Service;
+use hyper::Uri;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::net::TcpStream;
+use url::Url;
+
+use crate::proxy::Proxy;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Connects to the proxy instead of the endpoint when the `Proxy` applies to it
+///
+/// HTTPS endpoints are tunneled with `CONNECT`, TLS is then negotiated with the endpoint
+/// through the tunnel by the wrapping `HttpsConnector`. Requests to HTTP endpoints are sent
+/// to the proxy as is, with an absolute url.
+#[derive(Clone)]
+pub struct ProxyConnector {
+    http: HttpConnector,
+    proxy: Option<Arc<Proxy>>,
+}
+
+impl ProxyConnector {
+    pub fn new(http: HttpConnector, proxy: Option<Proxy>) -> ProxyConnector {
+        ProxyConnector {
+            http,
+            proxy: proxy.map(Arc::new),
+        }
+    }
+}
+
+/// Connection made by `ProxyConnector`
+pub struct ProxyStream {
+    stream: TcpStream,
+    /// Connected to a proxy the requests themselves are sent to, not tunneled.
+    forwarding: bool,
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = ProxyStream;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<ProxyStream, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        self.http.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        // Without a TLS implementation this is the outermost connector, HTTPS endpoints would
+        // silently get plain HTTP
+        #[cfg(not(any(feature = "use-tokio-native-tls", feature = "tokio-rustls-tls")))]
+        if dst.scheme_str() == Some("https") {
+            return Box::pin(async {
+                Err(
+                    "HTTPS endpoints need the use-tokio-native-tls or tokio-rustls-tls feature"
+                        .into(),
+                )
+            });
+        }
+
+        let proxy = Url::parse(&dst.to_string()).ok().and_then(|url| {
+            let proxy = self.proxy.as_ref()?.proxy_for(&url)?;
+            Some(proxy.clone())
+        });
+        let mut http = self.http.clone();
+
+        Box::pin(async move {
+            let proxy = match proxy {
+                Some(proxy) => proxy,
+                None => {
+                    let stream = http.call(dst).await?;
+                    return Ok(ProxyStream {
+                        stream,
+                        forwarding: false,
+                    });
+                }
+            };
+
+            let proxy_uri: Uri = format!(
+                "http://{}:{}",
+                proxy.host_str().unwrap_or_default(),
+                proxy.port_or_known_default().unwrap_or(80)
+            )
+            .parse()?;
+            let mut stream = http.call(proxy_uri).await?;
+
+            if dst.scheme_str() != Some("https") {
+                return Ok(ProxyStream {
+                    stream,
+                    forwarding: true,
+                });
+            }
+
+            let host = dst.host().unwrap_or_default();
+            let port = dst.port_u16().unwrap_or(443);
+            tunnel(&mut stream, host, port, Proxy::basic_auth(&proxy)).await?;
+            Ok(ProxyStream {
+                stream,
+                forwarding: false,
+            })
+        })
+    }
+}
+
+/// Ask the proxy for a tunnel to `host:port`.
+async fn tunnel(
+    stream: &mut TcpStream,
+    host: &str,
+    port: u16,
+    authorization: Option<String>,
+) -> io::Result<()> {
+    let mut request = format!(
+        "CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n",
+        host = host,
+        port = port
+    );
+    if let Some(authorization) = authorization {
+        request.push_str(&format!("Proxy-Authorization: {}\r\n", authorization));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = Vec::new();
+    let mut buf = [0; 1024];
+    while !response.ends_with(b"\r\n\r\n") {
+        // The tunnel is ours once the proxy is done with its headers, read byte by byte to
+        // not consume anything the endpoint sends
+        let n = stream.read(&mut buf[..1]).await?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "proxy closed the connection during CONNECT",
+            ));
+        }
+        response.extend_from_slice(&buf[..n]);
+        if response.len() > 16 * 1024 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "proxy CONNECT response headers too long",
+            ));
+        }
+    }
+
+    let status_line = String::from_utf8_lossy(&response);
+    let status_line = status_line.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(()),
+        _ => Err(io::Error::other(format!(
+            "proxy refused CONNECT: {}",
+            status_line
+        ))),
+    }
+}
+
+impl Connection for ProxyStream {
+    fn connected(&self) -> Connected {
+        self.stream.connected().proxy(self.forwarding)
+    }
+}
+
+impl AsyncRead for ProxyStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for ProxyStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
@@ -2,54 +2,101 @@ use base64::engine::general_purpose;
 use base64::Engine;
 use hmac::Mac;
 use std::collections::HashMap;
-#[cfg(any(feature = "with-tokio", feature = "with-async-std"))]
+#[cfg(any(
+    feature = "with-tokio",
+    feature = "with-async-std",
+    feature = "with-futures"
+))]
 use std::pin::Pin;
 use time::format_description::well_known::Rfc2822;
 use time::OffsetDateTime;
 use url::Url;
 
-use crate::bucket::Bucket;
-use crate::command::Command;
-use crate::error::S3Error;
+use crate::bucket::{Bucket, SignatureVersion};
+use crate::bucket_ops::Acl;
+use crate::command::{Command, HttpMethod, Multipart};
+use crate::error::{ErrorResponse, S3Error};
+use crate::interceptor::InterceptedRequest;
+use crate::region::Region;
+use crate::request::trace::AttemptSpan;
+use crate::request::HttpBackend;
+use crate::serde_types::ContentRange;
 use crate::signing;
 use crate::LONG_DATETIME;
 use bytes::Bytes;
 use http::header::{
-    HeaderName, ACCEPT, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, DATE, HOST, RANGE,
+    HeaderName, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, DATE, ETAG,
+    EXPECT, HOST, RANGE, RETRY_AFTER, USER_AGENT,
 };
 use http::HeaderMap;
 use std::fmt::Write as _;
 
-#[cfg(feature = "with-async-std")]
+#[cfg(any(feature = "with-async-std", feature = "with-futures"))]
 use futures_util::Stream;
 
 #[cfg(feature = "with-tokio")]
 use tokio_stream::Stream;
 
+/// Body, status code and headers of an S3 response
 #[derive(Debug)]
-
 pub struct ResponseData {
     bytes: Bytes,
     status_code: u16,
     headers: HashMap<String, String>,
 }
 
-#[cfg(any(feature = "with-tokio", feature = "with-async-std"))]
+#[cfg(any(
+    feature = "with-tokio",
+    feature = "with-async-std",
+    feature = "with-futures"
+))]
 pub type DataStream = Pin<Box<dyn Stream<Item = StreamItem> + Send>>;
-#[cfg(any(feature = "with-tokio", feature = "with-async-std"))]
+#[cfg(any(
+    feature = "with-tokio",
+    feature = "with-async-std",
+    feature = "with-futures"
+))]
 pub type StreamItem = Result<Bytes, S3Error>;
 
-#[cfg(any(feature = "with-tokio", feature = "with-async-std"))]
+#[cfg(any(
+    feature = "with-tokio",
+    feature = "with-async-std",
+    feature = "with-futures"
+))]
 pub struct ResponseDataStream {
     pub bytes: DataStream,
     pub status_code: u16,
 }
 
-#[cfg(any(feature = "with-tokio", feature = "with-async-std"))]
+#[cfg(any(
+    feature = "with-tokio",
+    feature = "with-async-std",
+    feature = "with-futures"
+))]
 impl ResponseDataStream {
     pub fn bytes(&mut self) -> &mut DataStream {
         &mut self.bytes
     }
+
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+}
+
+#[cfg(any(
+    feature = "with-tokio",
+    feature = "with-async-std",
+    feature = "with-futures"
+))]
+impl Stream for ResponseDataStream {
+    type Item = StreamItem;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.bytes.as_mut().poll_next(cx)
+    }
 }
 
 impl From<ResponseData> for Vec<u8> {
@@ -94,6 +141,31 @@ impl ResponseData {
     pub fn headers(&self) -> HashMap<String, String> {
         self.headers.clone()
     }
+
+    /// Value of the header `name`, compared case insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// `Content-Range` of a ranged `GetObject` response, `None` for full responses.
+    pub fn content_range(&self) -> Option<ContentRange> {
+        self.header("content-range").and_then(ContentRange::parse)
+    }
+
+    /// User metadata of the object, the `x-amz-meta-*` headers with the prefix stripped.
+    pub fn metadata(&self) -> HashMap<String, String> {
+        self.headers
+            .iter()
+            .filter_map(|(key, value)| {
+                let key = key.to_lowercase();
+                let key = key.strip_prefix("x-amz-meta-")?;
+                Some((key.to_string(), value.clone()))
+            })
+            .collect()
+    }
 }
 
 use std::fmt;
@@ -122,7 +194,7 @@ pub trait Request {
         &self,
         writer: &mut T,
     ) -> Result<u16, S3Error>;
-    #[cfg(feature = "with-async-std")]
+    #[cfg(any(feature = "with-async-std", feature = "with-futures"))]
     async fn response_data_to_writer<T: futures_io::AsyncWrite + Send + Unpin>(
         &self,
         writer: &mut T,
@@ -132,13 +204,20 @@ pub trait Request {
         &self,
         writer: &mut T,
     ) -> Result<u16, S3Error>;
-    #[cfg(any(feature = "with-async-std", feature = "with-tokio"))]
+    #[cfg(any(
+        feature = "with-tokio",
+        feature = "with-async-std",
+        feature = "with-futures"
+    ))]
     async fn response_data_to_stream(&self) -> Result<ResponseDataStream, S3Error>;
     async fn response_header(&self) -> Result<(Self::HeaderMap, u16), S3Error>;
     fn datetime(&self) -> OffsetDateTime;
-    fn bucket(&self) -> Bucket;
-    fn command(&self) -> Command;
+    fn bucket(&self) -> &Bucket;
+    fn command(&self) -> &Command<'_>;
     fn path(&self) -> String;
+    /// Whether the request resends one redirected to another region or rejected for clock
+    /// skew, it isn't resent again
+    fn retried(&self) -> bool;
 
     async fn signing_key(&self) -> Result<Vec<u8>, S3Error> {
         signing::signing_key(
@@ -147,23 +226,60 @@ pub trait Request {
                 .bucket()
                 .secret_key()
                 .await?
-                .expect("Secret key must be provided to sign headers, found None"),
+                .ok_or(S3Error::Credentials(
+                    crate::creds::error::CredentialsError::ConfigMissingSecretKey,
+                ))?,
             &self.bucket().region(),
             "s3",
         )
     }
 
-    fn request_body(&self) -> Vec<u8> {
-        if let Command::PutObject { content, .. } = self.command() {
-            Vec::from(content)
-        } else if let Command::PutObjectTagging { tags } = self.command() {
-            Vec::from(tags)
-        } else if let Command::UploadPart { content, .. } = self.command() {
-            Vec::from(content)
-        } else if let Command::CompleteMultipartUpload { data, .. } = &self.command() {
+    /// Body of the request, object contents are shared with the command rather than copied.
+    fn request_body(&self) -> Bytes {
+        if let Command::PutObject { content, .. } | Command::UploadPart { content, .. } =
+            self.command()
+        {
+            return content.clone();
+        }
+        let body = if let Command::PutObjectTagging { tags, .. }
+        | Command::PutBucketTagging { tags } = self.command()
+        {
+            Vec::from(*tags)
+        } else if let Command::CompleteMultipartUpload { data, .. } = self.command() {
             let body = data.to_string();
             body.as_bytes().to_vec()
-        } else if let Command::CreateBucket { config } = &self.command() {
+        } else if let Command::DeleteObjects { data } = self.command() {
+            data.to_string().into_bytes()
+        } else if let Command::PutBucketVersioning { configuration } = self.command() {
+            configuration.to_string().into_bytes()
+        } else if let Command::PutBucketRequestPayment { configuration } = self.command() {
+            configuration.to_string().into_bytes()
+        } else if let Command::PutObjectLockConfiguration { configuration } = self.command() {
+            configuration.to_string().into_bytes()
+        } else if let Command::PutObjectRetention { retention } = self.command() {
+            retention.to_string().into_bytes()
+        } else if let Command::PutObjectLegalHold { legal_hold } = self.command() {
+            legal_hold.to_string().into_bytes()
+        } else if let Command::PutObjectAcl { acl } | Command::PutBucketAcl { acl } = self.command()
+        {
+            acl.body().map(String::into_bytes).unwrap_or_default()
+        } else if let Command::PutBucketPolicy { policy } = self.command() {
+            Vec::from(*policy)
+        } else if let Command::PutBucketCors { configuration } = self.command() {
+            configuration.to_string().into_bytes()
+        } else if let Command::PutBucketLifecycle { configuration } = self.command() {
+            configuration.to_string().into_bytes()
+        } else if let Command::PutBucketReplication { configuration } = self.command() {
+            configuration.to_string().into_bytes()
+        } else if let Command::PutBucketEncryption { configuration } = self.command() {
+            configuration.to_string().into_bytes()
+        } else if let Command::PutBucketWebsite { configuration } = self.command() {
+            configuration.to_string().into_bytes()
+        } else if let Command::SelectObjectContent { request } = self.command() {
+            request.to_string().into_bytes()
+        } else if let Command::RestoreObject { request } = self.command() {
+            request.to_string().into_bytes()
+        } else if let Command::CreateBucket { config } = self.command() {
             if let Some(payload) = config.location_constraint_payload() {
                 Vec::from(payload)
             } else {
@@ -171,7 +287,8 @@ pub trait Request {
             }
         } else {
             Vec::new()
-        }
+        };
+        Bytes::from(body)
     }
 
     fn long_date(&self) -> Result<String, S3Error> {
@@ -191,22 +308,38 @@ pub trait Request {
         let (expiry, custom_headers, custom_queries) = match self.command() {
             Command::PresignGet {
                 expiry_secs,
+                custom_headers,
                 custom_queries,
-            } => (expiry_secs, None, custom_queries),
+            } => (
+                *expiry_secs,
+                custom_headers.as_ref(),
+                custom_queries.as_ref(),
+            ),
             Command::PresignPut {
                 expiry_secs,
                 custom_headers,
-            } => (expiry_secs, custom_headers, None),
-            Command::PresignDelete { expiry_secs } => (expiry_secs, None, None),
+            } => (*expiry_secs, custom_headers.as_ref(), None),
+            Command::PresignDelete { expiry_secs } | Command::PresignHead { expiry_secs } => {
+                (*expiry_secs, None, None)
+            }
             _ => unreachable!(),
         };
 
+        if self.bucket().secret_key().await?.is_none() {
+            return self.unsigned_url(custom_queries);
+        }
+
+        if self.bucket().signature_version() == SignatureVersion::V2 {
+            return self
+                .presigned_v2(expiry, custom_headers, custom_queries)
+                .await;
+        }
+
         Ok(format!(
             "{}&X-Amz-Signature={}",
-            self.presigned_url_no_sig(expiry, custom_headers.as_ref(), custom_queries.as_ref())
+            self.presigned_url_no_sig(expiry, custom_headers, custom_queries)
                 .await?,
-            self.presigned_authorization(custom_headers.as_ref())
-                .await?
+            self.presigned_authorization(custom_headers).await?
         ))
     }
 
@@ -215,23 +348,118 @@ pub trait Request {
         let (expiry, custom_headers, custom_queries) = match self.command() {
             Command::PresignGet {
                 expiry_secs,
+                custom_headers,
                 custom_queries,
-            } => (expiry_secs, None, custom_queries),
+            } => (
+                *expiry_secs,
+                custom_headers.as_ref(),
+                custom_queries.as_ref(),
+            ),
             Command::PresignPut {
                 expiry_secs,
                 custom_headers,
-            } => (expiry_secs, custom_headers, None),
-            Command::PresignDelete { expiry_secs } => (expiry_secs, None, None),
+            } => (*expiry_secs, custom_headers.as_ref(), None),
+            Command::PresignDelete { expiry_secs } | Command::PresignHead { expiry_secs } => {
+                (*expiry_secs, None, None)
+            }
             _ => unreachable!(),
         };
 
+        if self.bucket().secret_key()?.is_none() {
+            return self.unsigned_url(custom_queries);
+        }
+
+        if self.bucket().signature_version() == SignatureVersion::V2 {
+            return self.presigned_v2(expiry, custom_headers, custom_queries);
+        }
+
         Ok(format!(
             "{}&X-Amz-Signature={}",
-            self.presigned_url_no_sig(expiry, custom_headers.as_ref(), custom_queries.as_ref())?,
-            self.presigned_authorization(custom_headers.as_ref())?
+            self.presigned_url_no_sig(expiry, custom_headers, custom_queries)?,
+            self.presigned_authorization(custom_headers)?
         ))
     }
 
+    /// Presigned url of an anonymous bucket, there is nothing to sign it with.
+    fn unsigned_url(
+        &self,
+        custom_queries: Option<&HashMap<String, String>>,
+    ) -> Result<String, S3Error> {
+        let url = self.url()?;
+        let mut query = signing::flatten_queries(custom_queries)?;
+        if url.query().is_none() && !query.is_empty() {
+            query.replace_range(..1, "?");
+        }
+        Ok(format!("{}{}", url, query))
+    }
+
+    /// V2 presigned url, signed over the expiry timestamp instead of a date.
+    async fn presigned_v2(
+        &self,
+        expiry: u32,
+        custom_headers: Option<&HeaderMap>,
+        custom_queries: Option<&HashMap<String, String>>,
+    ) -> Result<String, S3Error> {
+        let bucket = self.bucket();
+        let token = if let Some(security_token) = bucket.security_token().await? {
+            Some(security_token)
+        } else {
+            bucket.session_token().await?
+        };
+        let expires = (self.datetime().unix_timestamp() + i64::from(expiry)).to_string();
+
+        let mut headers = custom_headers.cloned().unwrap_or_default();
+        if let Some(token) = &token {
+            headers.insert(
+                HeaderName::from_static("x-amz-security-token"),
+                token.parse()?,
+            );
+        }
+
+        let mut url = self.url()?;
+        if let Some(custom_queries) = custom_queries {
+            for (k, v) in custom_queries {
+                url.query_pairs_mut().append_pair(k, v);
+            }
+        }
+
+        let string_to_sign = signing::string_to_sign_v2(
+            &self.command().http_verb().to_string(),
+            &headers,
+            &expires,
+            &self.canonical_resource_v2(&url),
+        )?;
+        let signature = signing::signature_v2(
+            &bucket
+                .secret_key()
+                .await?
+                .expect("Secret key must be provided to sign headers, found None"),
+            &string_to_sign,
+        )?;
+
+        let access_key = bucket.access_key().await?.unwrap_or_default();
+        url.query_pairs_mut()
+            .append_pair("AWSAccessKeyId", &access_key)
+            .append_pair("Expires", &expires)
+            .append_pair("Signature", &signature);
+        if let Some(token) = &token {
+            url.query_pairs_mut()
+                .append_pair("x-amz-security-token", token);
+        }
+
+        Ok(url.to_string())
+    }
+
+    /// V2 canonicalized resource of `url`, `/bucket/key` for both path and subdomain style.
+    fn canonical_resource_v2(&self, url: &Url) -> String {
+        let bucket = self.bucket();
+        match self.command() {
+            Command::ListBuckets => signing::canonical_resource_v2(None, url),
+            _ if bucket.is_path_style() => signing::canonical_resource_v2(None, url),
+            _ => signing::canonical_resource_v2(Some(&bucket.name()), url),
+        }
+    }
+
     async fn presigned_authorization(
         &self,
         custom_headers: Option<&HeaderMap>,
@@ -257,23 +485,30 @@ pub trait Request {
         let (expiry, custom_headers, custom_queries) = match self.command() {
             Command::PresignGet {
                 expiry_secs,
+                custom_headers,
                 custom_queries,
-            } => (expiry_secs, None, custom_queries),
+            } => (
+                *expiry_secs,
+                custom_headers.as_ref(),
+                custom_queries.as_ref(),
+            ),
             Command::PresignPut {
                 expiry_secs,
                 custom_headers,
-            } => (expiry_secs, custom_headers, None),
-            Command::PresignDelete { expiry_secs } => (expiry_secs, None, None),
+            } => (*expiry_secs, custom_headers.as_ref(), None),
+            Command::PresignDelete { expiry_secs } | Command::PresignHead { expiry_secs } => {
+                (*expiry_secs, None, None)
+            }
             _ => unreachable!(),
         };
 
         signing::canonical_request(
             &self.command().http_verb().to_string(),
             &self
-                .presigned_url_no_sig(expiry, custom_headers.as_ref(), custom_queries.as_ref())
+                .presigned_url_no_sig(expiry, custom_headers, custom_queries)
                 .await?,
             headers,
-            "UNSIGNED-PAYLOAD",
+            signing::UNSIGNED_PAYLOAD,
         )
     }
 
@@ -290,17 +525,23 @@ pub trait Request {
         } else {
             bucket.session_token().await?
         };
+        let url = self.url()?;
+        let mut query = signing::authorization_query_params_no_sig(
+            &self.bucket().access_key().await?.unwrap_or_default(),
+            &self.datetime(),
+            &self.bucket().region(),
+            expiry,
+            custom_headers,
+            token.as_ref(),
+        )?;
+        // `url` has a query already with the bucket's extra query
+        if url.query().is_some() {
+            query.replace_range(..1, "&");
+        }
         let url = Url::parse(&format!(
             "{}{}{}",
-            self.url()?,
-            &signing::authorization_query_params_no_sig(
-                &self.bucket().access_key().await?.unwrap_or_default(),
-                &self.datetime(),
-                &self.bucket().region(),
-                expiry,
-                custom_headers,
-                token.as_ref()
-            )?,
+            url,
+            query,
             &signing::flatten_queries(custom_queries)?,
         ))?;
 
@@ -320,17 +561,23 @@ pub trait Request {
         } else {
             bucket.session_token()?
         };
+        let url = self.url()?;
+        let mut query = signing::authorization_query_params_no_sig(
+            &self.bucket().access_key()?.unwrap_or_default(),
+            &self.datetime(),
+            &self.bucket().region(),
+            expiry,
+            custom_headers,
+            token.as_ref(),
+        )?;
+        // `url` has a query already with the bucket's extra query
+        if url.query().is_some() {
+            query.replace_range(..1, "&");
+        }
         let url = Url::parse(&format!(
             "{}{}{}",
-            self.url()?,
-            &signing::authorization_query_params_no_sig(
-                &self.bucket().access_key()?.unwrap_or_default(),
-                &self.datetime(),
-                &self.bucket().region(),
-                expiry,
-                custom_headers,
-                token.as_ref()
-            )?,
+            url,
+            query,
             &signing::flatten_queries(custom_queries)?,
         ))?;
 
@@ -369,12 +616,61 @@ pub trait Request {
             Command::CompleteMultipartUpload { upload_id, .. } => {
                 write!(url_str, "?uploadId={}", upload_id).expect("Could not write to url_str");
             }
+            Command::ListParts { upload_id, .. } => {
+                write!(url_str, "?uploadId={}", upload_id).expect("Could not write to url_str");
+            }
             Command::GetObjectTorrent => url_str.push_str("?torrent"),
+            Command::GetObjectAttributes { .. } => url_str.push_str("?attributes"),
+            Command::DeleteObjects { .. } => url_str.push_str("?delete"),
+            Command::GetBucketVersioning | Command::PutBucketVersioning { .. } => {
+                url_str.push_str("?versioning")
+            }
+            Command::GetBucketRequestPayment | Command::PutBucketRequestPayment { .. } => {
+                url_str.push_str("?requestPayment")
+            }
+            Command::GetObjectLockConfiguration | Command::PutObjectLockConfiguration { .. } => {
+                url_str.push_str("?object-lock")
+            }
+            Command::GetObjectRetention | Command::PutObjectRetention { .. } => {
+                url_str.push_str("?retention")
+            }
+            Command::GetObjectLegalHold | Command::PutObjectLegalHold { .. } => {
+                url_str.push_str("?legal-hold")
+            }
+            Command::GetObjectAcl
+            | Command::PutObjectAcl { .. }
+            | Command::GetBucketAcl
+            | Command::PutBucketAcl { .. } => url_str.push_str("?acl"),
+            Command::GetBucketPolicy
+            | Command::PutBucketPolicy { .. }
+            | Command::DeleteBucketPolicy => url_str.push_str("?policy"),
+            Command::GetBucketCors | Command::PutBucketCors { .. } | Command::DeleteBucketCors => {
+                url_str.push_str("?cors")
+            }
+            Command::GetBucketLifecycle
+            | Command::PutBucketLifecycle { .. }
+            | Command::DeleteBucketLifecycle => url_str.push_str("?lifecycle"),
+            Command::GetBucketReplication
+            | Command::PutBucketReplication { .. }
+            | Command::DeleteBucketReplication => url_str.push_str("?replication"),
             Command::PutObject { multipart, .. } => {
                 if let Some(multipart) = multipart {
                     url_str.push_str(&multipart.query_string())
                 }
             }
+            Command::UploadPartCopy {
+                part_number,
+                upload_id,
+                ..
+            } => url_str.push_str(&Multipart::new(*part_number, upload_id).query_string()),
+            Command::GetBucketEncryption
+            | Command::PutBucketEncryption { .. }
+            | Command::DeleteBucketEncryption => url_str.push_str("?encryption"),
+            Command::GetBucketWebsite
+            | Command::PutBucketWebsite { .. }
+            | Command::DeleteBucketWebsite => url_str.push_str("?website"),
+            Command::SelectObjectContent { .. } => url_str.push_str("?select&select-type=2"),
+            Command::RestoreObject { .. } => url_str.push_str("?restore"),
             _ => {}
         }
 
@@ -390,18 +686,20 @@ pub trait Request {
             continuation_token,
             start_after,
             max_keys,
-        } = self.command().clone()
+        } = self.command()
         {
             let mut query_pairs = url.query_pairs_mut();
-            delimiter.map(|d| query_pairs.append_pair("delimiter", &d));
+            delimiter
+                .as_deref()
+                .map(|d| query_pairs.append_pair("delimiter", d));
 
-            query_pairs.append_pair("prefix", &prefix);
+            query_pairs.append_pair("prefix", prefix);
             query_pairs.append_pair("list-type", "2");
             if let Some(token) = continuation_token {
-                query_pairs.append_pair("continuation-token", &token);
+                query_pairs.append_pair("continuation-token", token);
             }
             if let Some(start_after) = start_after {
-                query_pairs.append_pair("start-after", &start_after);
+                query_pairs.append_pair("start-after", start_after);
             }
             if let Some(max_keys) = max_keys {
                 query_pairs.append_pair("max-keys", &max_keys.to_string());
@@ -413,14 +711,16 @@ pub trait Request {
             delimiter,
             marker,
             max_keys,
-        } = self.command().clone()
+        } = self.command()
         {
             let mut query_pairs = url.query_pairs_mut();
-            delimiter.map(|d| query_pairs.append_pair("delimiter", &d));
+            delimiter
+                .as_deref()
+                .map(|d| query_pairs.append_pair("delimiter", d));
 
-            query_pairs.append_pair("prefix", &prefix);
+            query_pairs.append_pair("prefix", prefix);
             if let Some(marker) = marker {
-                query_pairs.append_pair("marker", &marker);
+                query_pairs.append_pair("marker", marker);
             }
             if let Some(max_keys) = max_keys {
                 query_pairs.append_pair("max-keys", &max_keys.to_string());
@@ -432,6 +732,7 @@ pub trait Request {
                 prefix,
                 delimiter,
                 key_marker,
+                upload_id_marker,
                 max_uploads,
             } => {
                 let mut query_pairs = url.query_pairs_mut();
@@ -440,15 +741,40 @@ pub trait Request {
                     query_pairs.append_pair("prefix", prefix);
                 }
                 if let Some(key_marker) = key_marker {
-                    query_pairs.append_pair("key-marker", &key_marker);
+                    query_pairs.append_pair("key-marker", key_marker);
+                }
+                if let Some(upload_id_marker) = upload_id_marker {
+                    query_pairs.append_pair("upload-id-marker", upload_id_marker);
                 }
                 if let Some(max_uploads) = max_uploads {
                     query_pairs.append_pair("max-uploads", max_uploads.to_string().as_str());
                 }
             }
-            Command::PutObjectTagging { .. }
-            | Command::GetObjectTagging
-            | Command::DeleteObjectTagging => {
+            Command::ListParts {
+                part_number_marker,
+                max_parts,
+                ..
+            } => {
+                let mut query_pairs = url.query_pairs_mut();
+                if let Some(part_number_marker) = part_number_marker {
+                    query_pairs.append_pair("part-number-marker", &part_number_marker.to_string());
+                }
+                if let Some(max_parts) = max_parts {
+                    query_pairs.append_pair("max-parts", &max_parts.to_string());
+                }
+            }
+            Command::PutObjectTagging { version_id, .. }
+            | Command::GetObjectTagging { version_id }
+            | Command::DeleteObjectTagging { version_id } => {
+                let mut query_pairs = url.query_pairs_mut();
+                query_pairs.append_pair("tagging", "");
+                if let Some(version_id) = version_id {
+                    query_pairs.append_pair("versionId", version_id);
+                }
+            }
+            Command::PutBucketTagging { .. }
+            | Command::GetBucketTagging
+            | Command::DeleteBucketTagging => {
                 url.query_pairs_mut().append_pair("tagging", "");
             }
             _ => {}
@@ -468,6 +794,32 @@ pub trait Request {
 
     #[maybe_async::maybe_async]
     async fn authorization(&self, headers: &HeaderMap) -> Result<String, S3Error> {
+        if self.bucket().signature_version() == SignatureVersion::V2 {
+            let string_to_sign = signing::string_to_sign_v2(
+                &self.command().http_verb().to_string(),
+                headers,
+                // Dated by x-amz-date, which is part of the canonicalized amz headers
+                "",
+                &self.canonical_resource_v2(&self.url()?),
+            )?;
+            let signature = signing::signature_v2(
+                &self
+                    .bucket()
+                    .secret_key()
+                    .await?
+                    .expect("Secret key must be provided to sign headers, found None"),
+                &string_to_sign,
+            )?;
+            return Ok(signing::authorization_header_v2(
+                &self
+                    .bucket()
+                    .access_key()
+                    .await?
+                    .expect("No access_key provided"),
+                &signature,
+            ));
+        }
+
         let canonical_request = self.canonical_request(headers)?;
         let string_to_sign = self.string_to_sign(&canonical_request)?;
         let mut hmac = signing::HmacSha256::new_from_slice(&self.signing_key().await?)?;
@@ -487,8 +839,10 @@ pub trait Request {
         )
     }
 
+    /// Headers covered by the signature, everything but `Authorization`, `Date`, `User-Agent`
+    /// and what interceptors add.
     #[maybe_async::maybe_async]
-    async fn headers(&self) -> Result<HeaderMap, S3Error> {
+    async fn signed_headers(&self) -> Result<HeaderMap, S3Error> {
         // Generate this once, but it's used in more than one place.
         let sha256 = self.command().sha256();
 
@@ -498,8 +852,13 @@ pub trait Request {
         let mut headers = HeaderMap::new();
 
         for (k, v) in self.bucket().extra_headers.iter() {
-            if k.as_str().starts_with("x-amz-meta-") {
-                // metadata is invalid on any multipart command other than initiate
+            if k.as_str().starts_with("x-amz-meta-")
+                || k.as_str().starts_with("x-amz-grant-")
+                || k == "x-amz-tagging"
+                || k == "x-amz-website-redirect-location"
+            {
+                // metadata and the other attributes of new objects are invalid on any
+                // multipart command other than initiate
                 match self.command() {
                     Command::UploadPart { .. }
                     | Command::AbortMultipartUpload { .. }
@@ -510,22 +869,89 @@ pub trait Request {
                     _ => (),
                 }
             }
+            if k.as_str()
+                .starts_with("x-amz-server-side-encryption-customer-")
+            {
+                // SSE-C keys only apply to commands that read or write object data
+                match self.command() {
+                    Command::PutObject { .. }
+                    | Command::PutObjectStream { .. }
+                    | Command::GetObject
+                    | Command::GetObjectRange { .. }
+                    | Command::HeadObject
+                    | Command::CopyObject { .. }
+                    | Command::InitiateMultipartUpload { .. }
+                    | Command::UploadPart { .. }
+                    | Command::UploadPartCopy { .. }
+                    | Command::GetObjectAttributes { .. }
+                    | Command::SelectObjectContent { .. } => (),
+                    _ => continue,
+                }
+            }
+            if k.as_str()
+                .starts_with("x-amz-copy-source-server-side-encryption-customer-")
+            {
+                match self.command() {
+                    Command::CopyObject { .. } | Command::UploadPartCopy { .. } => (),
+                    _ => continue,
+                }
+            }
             headers.insert(k.clone(), v.clone());
         }
 
+        if self.bucket().is_requester_pays() {
+            headers.insert(
+                HeaderName::from_static("x-amz-request-payer"),
+                "requester".parse()?,
+            );
+        }
+
         let host_header = self.host_header();
 
         headers.insert(HOST, host_header.parse()?);
 
         match self.command() {
             Command::CopyObject { from } => {
-                headers.insert(HeaderName::from_static("x-amz-copy-source"), from.parse()?);
+                headers.insert(
+                    HeaderName::from_static("x-amz-copy-source"),
+                    signing::uri_encode(from, false).parse()?,
+                );
+            }
+            Command::UploadPartCopy {
+                from,
+                range: (first, last),
+                ..
+            } => {
+                headers.insert(
+                    HeaderName::from_static("x-amz-copy-source"),
+                    signing::uri_encode(from, false).parse()?,
+                );
+                headers.insert(
+                    HeaderName::from_static("x-amz-copy-source-range"),
+                    format!("bytes={}-{}", first, last).parse()?,
+                );
             }
             Command::ListObjects { .. } => {}
             Command::ListObjectsV2 { .. } => {}
             Command::GetObject => {}
-            Command::GetObjectTagging => {}
+            Command::GetObjectTagging { .. } => {}
+            Command::GetBucketTagging => {}
             Command::GetBucketLocation => {}
+            Command::GetBucketVersioning => {}
+            Command::GetBucketRequestPayment => {}
+            Command::GetObjectLockConfiguration => {}
+            Command::GetObjectRetention => {}
+            Command::GetObjectLegalHold => {}
+            Command::GetObjectAcl => {}
+            Command::GetBucketAcl => {}
+            Command::GetBucketPolicy => {}
+            Command::GetBucketCors => {}
+            Command::GetBucketLifecycle => {}
+            Command::GetBucketReplication => {}
+            Command::GetBucketEncryption => {}
+            Command::GetBucketWebsite => {}
+            Command::ListParts { .. } => {}
+            Command::GetObjectAttributes { .. } => {}
             _ => {
                 headers.insert(
                     CONTENT_LENGTH,
@@ -538,10 +964,11 @@ pub trait Request {
             HeaderName::from_static("x-amz-content-sha256"),
             sha256.parse()?,
         );
-        headers.insert(
-            HeaderName::from_static("x-amz-date"),
-            self.long_date()?.parse()?,
-        );
+        let amz_date = match self.bucket().signature_version() {
+            SignatureVersion::V2 => self.datetime().format(&Rfc2822)?,
+            SignatureVersion::V4 => self.long_date()?,
+        };
+        headers.insert(HeaderName::from_static("x-amz-date"), amz_date.parse()?);
 
         if let Some(session_token) = self.bucket().session_token().await? {
             headers.insert(
@@ -555,7 +982,9 @@ pub trait Request {
             );
         }
 
-        if let Command::PutObjectTagging { tags } = self.command() {
+        if let Command::PutObjectTagging { tags, .. } | Command::PutBucketTagging { tags } =
+            self.command()
+        {
             let digest = md5::compute(tags);
             let hash = general_purpose::STANDARD.encode(digest.as_ref());
             headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
@@ -567,8 +996,78 @@ pub trait Request {
             let digest = md5::compute(content);
             let hash = general_purpose::STANDARD.encode(digest.as_ref());
             headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::DeleteObjects { data } = self.command() {
+            // Content-MD5 is mandatory for multi-object delete
+            let digest = md5::compute(data.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutBucketVersioning { configuration } = self.command() {
+            let digest = md5::compute(configuration.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutBucketRequestPayment { configuration } = self.command() {
+            let digest = md5::compute(configuration.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutObjectLockConfiguration { configuration } = self.command() {
+            let digest = md5::compute(configuration.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutObjectRetention { retention } = self.command() {
+            let digest = md5::compute(retention.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutObjectLegalHold { legal_hold } = self.command() {
+            let digest = md5::compute(legal_hold.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutBucketCors { configuration } = self.command() {
+            let digest = md5::compute(configuration.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutBucketLifecycle { configuration } = self.command() {
+            let digest = md5::compute(configuration.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutBucketReplication { configuration } = self.command() {
+            let digest = md5::compute(configuration.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutBucketEncryption { configuration } = self.command() {
+            let digest = md5::compute(configuration.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutBucketWebsite { configuration } = self.command() {
+            let digest = md5::compute(configuration.to_string());
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutBucketPolicy { policy } = self.command() {
+            let digest = md5::compute(policy);
+            let hash = general_purpose::STANDARD.encode(digest.as_ref());
+            headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+        } else if let Command::PutObjectAcl { acl } | Command::PutBucketAcl { acl } = self.command()
+        {
+            match acl {
+                Acl::Canned(canned) => {
+                    headers.insert(
+                        HeaderName::from_static("x-amz-acl"),
+                        canned.to_string().parse()?,
+                    );
+                }
+                Acl::Policy(policy) => {
+                    let digest = md5::compute(policy.to_string());
+                    let hash = general_purpose::STANDARD.encode(digest.as_ref());
+                    headers.insert(HeaderName::from_static("content-md5"), hash.parse()?);
+                }
+            }
         } else if let Command::GetObject {} = self.command() {
             headers.insert(ACCEPT, "application/octet-stream".to_string().parse()?);
+            if self.bucket().checksum_validation() {
+                headers.insert(
+                    HeaderName::from_static("x-amz-checksum-mode"),
+                    "ENABLED".parse()?,
+                );
+            }
         // headers.insert(header::ACCEPT_CHARSET, HeaderValue::from_str("UTF-8")?);
         } else if let Command::GetObjectRange { start, end } = self.command() {
             headers.insert(ACCEPT, "application/octet-stream".to_string().parse()?);
@@ -580,10 +1079,118 @@ pub trait Request {
             }
 
             headers.insert(RANGE, range.parse()?);
-        } else if let Command::CreateBucket { ref config } = self.command() {
+        } else if let Command::CreateBucket { config } = self.command() {
             config.add_headers(&mut headers)?;
+        } else if let Command::PutObjectStream {
+            content_length,
+            checksum,
+            ..
+        } = self.command()
+        {
+            headers.insert(
+                HeaderName::from_static("content-encoding"),
+                "aws-chunked".parse()?,
+            );
+            headers.insert(
+                HeaderName::from_static("x-amz-decoded-content-length"),
+                content_length.to_string().parse()?,
+            );
+            if let Some(algorithm) = checksum {
+                // The checksum follows the body, see `ChunkSigner::sign_trailer`
+                headers.insert(
+                    HeaderName::from_static("x-amz-trailer"),
+                    algorithm.header_name().parse()?,
+                );
+                headers.insert(
+                    HeaderName::from_static("x-amz-sdk-checksum-algorithm"),
+                    algorithm.as_str().parse()?,
+                );
+            }
+        } else if let Command::GetObjectAttributes {
+            attributes,
+            part_number_marker,
+            max_parts,
+        } = self.command()
+        {
+            let attributes = attributes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            headers.insert(
+                HeaderName::from_static("x-amz-object-attributes"),
+                attributes.parse()?,
+            );
+            if let Some(part_number_marker) = part_number_marker {
+                headers.insert(
+                    HeaderName::from_static("x-amz-part-number-marker"),
+                    part_number_marker.to_string().parse()?,
+                );
+            }
+            if let Some(max_parts) = max_parts {
+                headers.insert(
+                    HeaderName::from_static("x-amz-max-parts"),
+                    max_parts.to_string().parse()?,
+                );
+            }
         }
 
+        if let Some(algorithm) = self.bucket().checksum_algorithm() {
+            match self.command() {
+                Command::PutObject { content, .. } | Command::UploadPart { content, .. } => {
+                    headers.insert(
+                        HeaderName::from_static(algorithm.header_name()),
+                        algorithm.checksum(content).parse()?,
+                    );
+                    headers.insert(
+                        HeaderName::from_static("x-amz-sdk-checksum-algorithm"),
+                        algorithm.as_str().parse()?,
+                    );
+                }
+                Command::InitiateMultipartUpload { .. } => {
+                    headers.insert(
+                        HeaderName::from_static("x-amz-checksum-algorithm"),
+                        algorithm.as_str().parse()?,
+                    );
+                }
+                _ => (),
+            }
+        }
+
+        Ok(headers)
+    }
+
+    /// Canonical request, string to sign and signed headers of this request, for comparing
+    /// against what a provider answering `SignatureDoesNotMatch` computed.
+    #[cfg(feature = "debug-signing")]
+    #[maybe_async::maybe_async]
+    async fn signing_debug(&self) -> Result<signing::SigningDebug, S3Error> {
+        let headers = self.signed_headers().await?;
+        if self.bucket().signature_version() == SignatureVersion::V2 {
+            // V2 has no canonical request, its string to sign lists the headers itself
+            return Ok(signing::SigningDebug {
+                canonical_request: String::new(),
+                string_to_sign: signing::string_to_sign_v2(
+                    &self.command().http_verb().to_string(),
+                    &headers,
+                    "",
+                    &self.canonical_resource_v2(&self.url()?),
+                )?,
+                signed_headers: String::new(),
+            });
+        }
+        let canonical_request = self.canonical_request(&headers)?;
+        Ok(signing::SigningDebug {
+            string_to_sign: self.string_to_sign(&canonical_request)?,
+            canonical_request,
+            signed_headers: signing::signed_header_string(&headers),
+        })
+    }
+
+    #[maybe_async::maybe_async]
+    async fn headers(&self) -> Result<HeaderMap, S3Error> {
+        let mut headers = self.signed_headers().await?;
+
         // This must be last, as it signs the other headers, omitted if no secret key is provided
         if self.bucket().secret_key().await?.is_some() {
             let authorization = self.authorization(&headers).await?;
@@ -598,6 +1205,272 @@ pub trait Request {
         // the signed headers.
         headers.insert(DATE, self.datetime().format(&Rfc2822)?.parse()?);
 
+        // Proxies rewrite the User-Agent, so it's left out of the signed headers as well.
+        // One set with `add_header` is kept, and signed.
+        if !headers.contains_key(USER_AGENT) {
+            headers.insert(USER_AGENT, self.bucket().user_agent().clone());
+        }
+
+        // Hop-by-hop, so not signed either. hyper and an `HttpBackend` do the handshake, surf
+        // and attohttpc would take the `100 Continue` for the final response.
+        let handshake = cfg!(feature = "with-tokio") || self.bucket().http_backend().is_some();
+        let expect_continue = self.bucket().expect_continue().filter(|_| handshake);
+        if let Some(threshold) = expect_continue {
+            if let Command::PutObject { .. }
+            | Command::PutObjectStream { .. }
+            | Command::UploadPart { .. } = self.command()
+            {
+                if self.command().content_length() as u64 > threshold {
+                    headers.insert(EXPECT, HeaderValue::from_static("100-continue"));
+                }
+            }
+        }
+
+        let bucket = self.bucket();
+        if !bucket.interceptors().is_empty() {
+            let url = self.url()?;
+            let mut request =
+                InterceptedRequest::new(self.command().http_verb(), &url, &mut headers);
+            for interceptor in bucket.interceptors() {
+                interceptor.before_send(&mut request)?;
+            }
+        }
+
         Ok(headers)
     }
+
+    /// `response` sent by `backend` instead of the built-in client, retried according to the
+    /// bucket's `RetryPolicy`.
+    #[maybe_async::maybe_async]
+    async fn backend_response(
+        &self,
+        backend: &dyn HttpBackend,
+    ) -> Result<http::Response<Bytes>, S3Error> {
+        let headers = self.headers().await?;
+        let method = match self.command().http_verb() {
+            HttpMethod::Delete => http::Method::DELETE,
+            HttpMethod::Get => http::Method::GET,
+            HttpMethod::Post => http::Method::POST,
+            HttpMethod::Put => http::Method::PUT,
+            HttpMethod::Head => http::Method::HEAD,
+        };
+        let url = self.url()?;
+        let body = self.request_body();
+
+        let bucket = self.bucket();
+        let retry_policy = bucket.retry_policy();
+        let mut attempt = 1;
+        let response = loop {
+            let mut request = http::Request::builder()
+                .method(method.clone())
+                .uri(url.as_str());
+            for (name, value) in headers.iter() {
+                request = request.header(name, value);
+            }
+
+            let span = AttemptSpan::start(self, attempt);
+            let response = backend.send(request.body(body.clone())?).await;
+            span.finish(response.as_ref().map(|response| response.status().as_u16()));
+            let delay = match &response {
+                Ok(response) => retry_policy.retry_status(
+                    attempt,
+                    response.status().as_u16(),
+                    response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|retry_after| retry_after.to_str().ok()),
+                ),
+                Err(e) => retry_policy.retry_error(attempt, e),
+            };
+            match delay {
+                Some(delay) => {
+                    // In blocks, `maybe_async` drops the attributes of an `.await` it strips
+                    #[cfg(feature = "with-tokio")]
+                    {
+                        tokio::time::sleep(delay).await;
+                    }
+                    #[cfg(feature = "with-async-std")]
+                    {
+                        async_std::task::sleep(delay).await;
+                    }
+                    #[cfg(all(feature = "with-futures", not(feature = "with-wasm")))]
+                    {
+                        futures_timer::Delay::new(delay).await;
+                    }
+                    #[cfg(feature = "with-wasm")]
+                    {
+                        crate::request::wasm_backend::sleep(delay).await;
+                    }
+                    #[cfg(feature = "sync")]
+                    std::thread::sleep(delay);
+                }
+                None => break response?,
+            }
+            attempt += 1;
+        };
+
+        let bucket_region = response
+            .headers()
+            .get(BUCKET_REGION)
+            .and_then(|region| region.to_str().ok());
+        if let Some(bucket) = redirect_bucket(bucket, response.status().as_u16(), bucket_region)
+            .filter(|_| !self.retried())
+        {
+            let path = self.path();
+            let mut request =
+                crate::bucket::RequestImpl::new(&bucket, &path, self.command().clone()).await?;
+            request.retried = true;
+            return request.backend_response(backend).await;
+        }
+
+        let date = response
+            .headers()
+            .get(DATE)
+            .and_then(|date| date.to_str().ok());
+        if let Some(server_time) =
+            skewed_server_time(response.status().as_u16(), date, self.datetime())
+                .filter(|_| !self.retried())
+        {
+            if correct_clock_skew(bucket, server_time, response.body()) {
+                let path = self.path();
+                let mut request =
+                    crate::bucket::RequestImpl::new(bucket, &path, self.command().clone()).await?;
+                request.retried = true;
+                return request.backend_response(backend).await;
+            }
+        }
+
+        if cfg!(feature = "fail-on-err") && !response.status().is_success() {
+            return Err(ErrorResponse::from_headers(
+                response.status().as_u16(),
+                response.headers(),
+                response.body(),
+            )
+            .into());
+        }
+        Ok(response)
+    }
+
+    /// `response_data` sent by `backend` instead of the built-in client.
+    #[maybe_async::maybe_async]
+    async fn backend_response_data(
+        &self,
+        backend: &dyn HttpBackend,
+        etag: bool,
+    ) -> Result<ResponseData, S3Error> {
+        let response = self.backend_response(backend).await?;
+        let status_code = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.to_string(),
+                    v.to_str()
+                        .unwrap_or("could-not-decode-header-value")
+                        .to_string(),
+                )
+            })
+            .collect::<HashMap<String, String>>();
+        let body = if etag {
+            match response.headers().get(ETAG) {
+                Some(etag) => Bytes::from(etag.to_str()?.to_string()),
+                None => Bytes::from(""),
+            }
+        } else {
+            response.into_body()
+        };
+        Ok(ResponseData::new(body, status_code, headers))
+    }
+
+    /// `response_data_to_stream` sent by `backend` instead of the built-in client, the body
+    /// arrives as a single chunk.
+    #[cfg(any(
+        feature = "with-tokio",
+        feature = "with-async-std",
+        feature = "with-futures"
+    ))]
+    async fn backend_response_data_to_stream(
+        &self,
+        backend: &dyn HttpBackend,
+    ) -> Result<ResponseDataStream, S3Error> {
+        let response = self.backend_response(backend).await?;
+        crate::request::etag_check::verify_response(self.bucket(), self.command(), &response)?;
+        let status_code = response.status().as_u16();
+        let body = response.into_body();
+        Ok(ResponseDataStream {
+            bytes: Box::pin(futures::stream::once(async move { Ok::<_, S3Error>(body) })),
+            status_code,
+        })
+    }
+}
+
+/// Header with the region of the bucket, sent along with requests for another region
+pub(crate) const BUCKET_REGION: &str = "x-amz-bucket-region";
+
+/// The time of S3 by the `Date` of a response with `status`, when it's a 403 more than 15
+/// minutes off `datetime` of the request, like S3 rejects requests off its clock. Only then the
+/// body is read, to tell whether it's a `RequestTimeTooSkewed` error, see `correct_clock_skew`.
+pub(crate) fn skewed_server_time(
+    status: u16,
+    date: Option<&str>,
+    datetime: OffsetDateTime,
+) -> Option<OffsetDateTime> {
+    if status != 403 {
+        return None;
+    }
+    let server_time = date.and_then(crate::serde_types::parse_http_date)?;
+    if (server_time - datetime).abs() < time::Duration::minutes(15) {
+        return None;
+    }
+    Some(server_time)
+}
+
+/// Whether the error `body` S3 answered with at `server_time`, see `skewed_server_time`, is a
+/// `RequestTimeTooSkewed` one, and the request should be signed again. The difference is stored
+/// on `bucket`, see `Bucket::clock_skew`.
+pub(crate) fn correct_clock_skew(
+    bucket: &Bucket,
+    server_time: OffsetDateTime,
+    body: &[u8],
+) -> bool {
+    let error = ErrorResponse::new(403, None, None, body);
+    if error.code.as_deref() != Some("RequestTimeTooSkewed") {
+        return false;
+    }
+    bucket.set_clock_skew(server_time - crate::utils::now_utc());
+    true
+}
+
+/// The bucket to send a request answered with `status` again with, when `bucket` discovers
+/// regions and `bucket_region` is another AWS region, see `Bucket::with_region_discovery`.
+pub(crate) fn redirect_bucket(
+    bucket: &Bucket,
+    status: u16,
+    bucket_region: Option<&str>,
+) -> Option<Bucket> {
+    if !bucket.region_discovery() || !matches!(status, 301 | 307 | 400) || !bucket.region().is_aws()
+    {
+        return None;
+    }
+    let name = bucket_region?;
+    let region = match name.parse::<Region>().ok()? {
+        // A region newer than `Region`
+        Region::Custom { .. } => {
+            let domain = if name.starts_with("cn-") {
+                "amazonaws.com.cn"
+            } else {
+                "amazonaws.com"
+            };
+            Region::Custom {
+                region: name.to_string(),
+                endpoint: format!("https://s3.{}.{}", name, domain),
+            }
+        }
+        region => region,
+    };
+    if region == bucket.region() {
+        return None;
+    }
+    Some(bucket.with_discovered_region(region))
 }
@@ -0,0 +1,91 @@
+//! Hooks around every attempt at sending a request: `tracing` spans, with the `tracing`
+//! feature, and the bucket's interceptors
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use time::OffsetDateTime;
+
+use crate::error::S3Error;
+use crate::interceptor::{InterceptedResponse, Interceptor};
+use crate::request::Request;
+use crate::utils::now_utc;
+
+/// An attempt at sending a request
+///
+/// With the `tracing` feature it's a `s3.request` span carrying `operation`, `bucket`, `key`
+/// and `attempt`, `status` and `latency_ms` are recorded when the attempt is finished, along
+/// with a `DEBUG` event, `WARN` for failures. The interceptors of the bucket are told about
+/// the outcome.
+pub(crate) struct AttemptSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    bucket: String,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    operation: &'static str,
+    path: String,
+    attempt: u32,
+    started: OffsetDateTime,
+}
+
+impl AttemptSpan {
+    pub(crate) fn start<R: Request + ?Sized>(request: &R, attempt: u32) -> AttemptSpan {
+        let bucket = request.bucket();
+        let operation = request.command().name();
+        let path = request.path();
+        AttemptSpan {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "s3.request",
+                operation,
+                bucket = %bucket.name(),
+                key = %path,
+                attempt,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+            ),
+            bucket: bucket.name(),
+            interceptors: bucket.interceptors().to_vec(),
+            operation,
+            path,
+            attempt,
+            started: now_utc(),
+        }
+    }
+
+    /// Record the status the attempt was answered with, or the error it failed with.
+    pub(crate) fn finish(self, outcome: Result<u16, &S3Error>) {
+        let latency: std::time::Duration =
+            (now_utc() - self.started).try_into().unwrap_or_default();
+
+        #[cfg(feature = "tracing")]
+        {
+            let latency_ms = latency.as_millis() as u64;
+            self.span.record("latency_ms", latency_ms);
+            match outcome {
+                Ok(status) => {
+                    self.span.record("status", status);
+                    tracing::debug!(parent: &self.span, status, latency_ms, "s3 request finished");
+                }
+                Err(error) => {
+                    tracing::warn!(parent: &self.span, %error, latency_ms, "s3 request failed");
+                }
+            }
+        }
+
+        if self.interceptors.is_empty() {
+            return;
+        }
+        let response = InterceptedResponse {
+            operation: self.operation,
+            bucket: &self.bucket,
+            path: &self.path,
+            attempt: self.attempt,
+            outcome,
+            latency,
+        };
+        for interceptor in &self.interceptors {
+            interceptor.on_response(&response);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! `fetch` backend for `wasm32-unknown-unknown`, in browsers, workers and Node
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::channel::oneshot;
+use http::header::{CONTENT_LENGTH, DATE, HOST};
+use maybe_async::maybe_async;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+use crate::error::S3Error;
+use crate::request::HttpBackend;
+
+#[wasm_bindgen]
+extern "C" {
+    /// The global `fetch`, not `window.fetch`, which doesn't exist in workers and Node
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch_with_request(request: &web_sys::Request) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, timeout: i32) -> JsValue;
+}
+
+/// Sends requests with the JavaScript `fetch` API
+///
+/// The endpoint has to allow the page's origin with a CORS rule, see `Bucket::put_bucket_cors`,
+/// and expose the response headers the caller reads (`ETag`, ...).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FetchBackend;
+
+#[maybe_async]
+impl HttpBackend for FetchBackend {
+    async fn send(&self, request: http::Request<Bytes>) -> Result<http::Response<Bytes>, S3Error> {
+        // JS futures are bound to the thread they were made on, they are driven by the JS
+        // event loop and only the result crosses over
+        let (sender, receiver) = oneshot::channel();
+        spawn_local(async move {
+            let _ = sender.send(fetch(request).await);
+        });
+        receiver
+            .await
+            .map_err(|_| S3Error::Fetch("fetch was cancelled".to_string()))?
+    }
+}
+
+async fn fetch(request: http::Request<Bytes>) -> Result<http::Response<Bytes>, S3Error> {
+    let (parts, body) = request.into_parts();
+
+    let headers = web_sys::Headers::new().map_err(js_error)?;
+    for (name, value) in parts.headers.iter() {
+        // Set by the browser, which refuses them from scripts
+        if name == HOST || name == CONTENT_LENGTH || name == DATE {
+            continue;
+        }
+        headers
+            .append(name.as_str(), value.to_str()?)
+            .map_err(js_error)?;
+    }
+
+    let init = web_sys::RequestInit::new();
+    init.set_method(parts.method.as_str());
+    init.set_headers(&headers);
+    if !body.is_empty() {
+        init.set_body(&js_sys::Uint8Array::from(body.as_ref()));
+    }
+    let request =
+        web_sys::Request::new_with_str_and_init(&parts.uri.to_string(), &init).map_err(js_error)?;
+
+    let response: web_sys::Response = JsFuture::from(fetch_with_request(&request))
+        .await
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+
+    let mut builder = http::Response::builder().status(response.status());
+    let entries = js_sys::try_iter(&response.headers())
+        .map_err(js_error)?
+        .ok_or_else(|| S3Error::Fetch("response headers are not iterable".to_string()))?;
+    for entry in entries {
+        let entry = js_sys::Array::from(&entry.map_err(js_error)?);
+        if let (Some(name), Some(value)) = (entry.get(0).as_string(), entry.get(1).as_string()) {
+            builder = builder.header(name, value);
+        }
+    }
+
+    let body = JsFuture::from(response.array_buffer().map_err(js_error)?)
+        .await
+        .map_err(js_error)?;
+    Ok(builder.body(Bytes::from(js_sys::Uint8Array::new(&body).to_vec()))?)
+}
+
+fn js_error(value: JsValue) -> S3Error {
+    S3Error::Fetch(format!("{:?}", value))
+}
+
+/// Wait for `duration` on the JS event loop, the retry delay of `RetryPolicy`
+pub(crate) async fn sleep(duration: Duration) {
+    let (sender, receiver) = oneshot::channel();
+    spawn_local(async move {
+        let timeout = duration.as_millis().min(i32::MAX as u128) as i32;
+        let promise = js_sys::Promise::new(&mut |resolve, _| {
+            set_timeout(&resolve, timeout);
+        });
+        let _ = JsFuture::from(promise).await;
+        let _ = sender.send(());
+    });
+    let _ = receiver.await;
+}
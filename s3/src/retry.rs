@@ -0,0 +1,180 @@
+//! Retrying transient failures with exponential backoff and jitter
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::error::S3Error;
+
+/// When and how often failed requests are sent again
+///
+/// A request is retried when it fails with a transport error or S3 answers with one of
+/// `retryable_status`, until `max_attempts` requests were sent. Between attempts it waits a
+/// random duration between zero and `base_delay * 2^(attempt - 1)`, capped at `max_delay`
+/// ("full jitter"), or as long as a `Retry-After` header asks for.
+///
+/// ```
+/// use std::time::Duration;
+/// use s3::retry::RetryPolicy;
+///
+/// let policy = RetryPolicy::default()
+///     .with_max_attempts(5)
+///     .with_max_delay(Duration::from_secs(10));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Requests sent at most, including the first one. 1 disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// 500 (InternalError), 503 (SlowDown) and friends by default.
+    pub retryable_status: Vec<u16>,
+    /// Retry connection resets, timeouts and other transport errors.
+    pub retry_transport_errors: bool,
+    /// Wait as long as a `Retry-After` header asks for, capped at `max_delay`.
+    pub honor_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(20),
+            retryable_status: vec![429, 500, 502, 503, 504],
+            retry_transport_errors: true,
+            honor_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Send every request once, failures are returned straight away.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn with_retryable_status(mut self, retryable_status: Vec<u16>) -> Self {
+        self.retryable_status = retryable_status;
+        self
+    }
+
+    pub fn with_retry_transport_errors(mut self, retry_transport_errors: bool) -> Self {
+        self.retry_transport_errors = retry_transport_errors;
+        self
+    }
+
+    pub fn with_honor_retry_after(mut self, honor_retry_after: bool) -> Self {
+        self.honor_retry_after = honor_retry_after;
+        self
+    }
+
+    /// How long to wait before sending the request again after `attempt` (1 based) got a
+    /// response with `status`, `None` to return the response.
+    pub fn retry_status(
+        &self,
+        attempt: u32,
+        status: u16,
+        retry_after: Option<&str>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.retryable_status.contains(&status) {
+            return None;
+        }
+        let retry_after = retry_after
+            .filter(|_| self.honor_retry_after)
+            .and_then(|retry_after| retry_after.trim().parse::<u64>().ok());
+        Some(match retry_after {
+            Some(seconds) => std::cmp::min(Duration::from_secs(seconds), self.max_delay),
+            None => self.backoff(attempt),
+        })
+    }
+
+    /// How long to wait before sending the request again after `attempt` (1 based) failed
+    /// with `error`, `None` to return the error.
+    pub fn retry_error(&self, attempt: u32, error: &S3Error) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.retry_transport_errors {
+            return None;
+        }
+        if error.is_transport_error() {
+            Some(self.backoff(attempt))
+        } else {
+            None
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let cap = self
+            .base_delay
+            .checked_mul(1 << std::cmp::min(attempt.saturating_sub(1), 16))
+            .map_or(self.max_delay, |delay| std::cmp::min(delay, self.max_delay));
+        let cap_nanos = cap.as_nanos() as u64;
+        if cap_nanos == 0 {
+            return cap;
+        }
+        Duration::from_nanos(jitter() % (cap_nanos + 1))
+    }
+}
+
+/// A random number, `RandomState` is seeded randomly per thread and per call.
+fn jitter() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::RetryPolicy;
+    use crate::error::S3Error;
+    use std::time::Duration;
+
+    #[test]
+    fn test_retry_status() {
+        let policy = RetryPolicy::default().with_base_delay(Duration::from_millis(100));
+
+        for attempt in 1..3 {
+            let delay = policy.retry_status(attempt, 503, None).unwrap();
+            assert!(delay <= Duration::from_millis(100 << (attempt - 1)));
+        }
+        // Out of attempts
+        assert_eq!(policy.retry_status(3, 503, None), None);
+        assert_eq!(policy.retry_status(1, 404, None), None);
+
+        assert_eq!(
+            policy.retry_status(1, 503, Some("2")),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            policy.retry_status(1, 503, Some("3600")),
+            Some(policy.max_delay)
+        );
+    }
+
+    #[test]
+    fn test_retry_error() {
+        let policy = RetryPolicy::default();
+        let reset = S3Error::Io(std::io::ErrorKind::ConnectionReset.into());
+
+        assert!(policy.retry_error(1, &reset).is_some());
+        assert!(policy.retry_error(1, &S3Error::HttpFail).is_none());
+        assert!(RetryPolicy::none().retry_error(1, &reset).is_none());
+    }
+}
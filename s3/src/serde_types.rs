@@ -8,6 +8,20 @@ pub struct InitiateMultipartUploadResponse {
     pub upload_id: String,
 }
 
+/// The parsed result of a server side object copy
+#[derive(Deserialize, Debug, Clone)]
+pub struct CopyObjectResult {
+    #[serde(rename = "ETag")]
+    /// The entity tag of the newly created object.
+    pub e_tag: String,
+    #[serde(rename = "LastModified")]
+    /// Date and time the new object was last modified.
+    pub last_modified: String,
+}
+
+/// The parsed result of an `UploadPartCopy`, identical in shape to `CopyObjectResult`
+pub type CopyPartResult = CopyObjectResult;
+
 /// Owner information for the object
 #[derive(Deserialize, Debug, Clone)]
 pub struct Owner {
@@ -63,7 +77,38 @@ pub struct MultipartUpload {
     pub id: String,
 }
 
+impl MultipartUpload {
+    /// `initiated` parsed as a timestamp.
+    pub fn initiated_at(&self) -> Result<OffsetDateTime, S3Error> {
+        Ok(OffsetDateTime::parse(&self.initiated, &Rfc3339)?)
+    }
+}
+
+use crate::checksum::{ChecksumAlgorithm, ChecksumValue};
+use crate::command::HttpMethod;
+use crate::error::S3Error;
+use http::header::{IF_MODIFIED_SINCE, IF_NONE_MATCH};
+use http::HeaderMap;
+use quick_xml::escape::escape;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
 use std::fmt;
+use time::format_description::well_known::Rfc3339;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+/// IMF-fixdate, the format of the `Last-Modified` header
+pub(crate) const HTTP_DATE: &[FormatItem<'static>] = format_description!(
+    "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+);
+
+/// `Last-Modified` style timestamp, `None` if malformed.
+pub(crate) fn parse_http_date(date: &str) -> Option<OffsetDateTime> {
+    PrimitiveDateTime::parse(date, HTTP_DATE)
+        .ok()
+        .map(PrimitiveDateTime::assume_utc)
+}
 
 impl fmt::Display for CompleteMultipartUploadData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -81,11 +126,11 @@ impl fmt::Display for CompleteMultipartUploadData {
 
 impl CompleteMultipartUploadData {
     pub fn len(&self) -> usize {
-        self.to_string().as_bytes().len()
+        self.to_string().len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.to_string().as_bytes().len() == 0
+        self.to_string().is_empty()
     }
 }
 
@@ -94,12 +139,84 @@ pub struct CompleteMultipartUploadData {
     pub parts: Vec<Part>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Part {
     #[serde(rename = "PartNumber")]
     pub part_number: u32,
     #[serde(rename = "ETag")]
     pub etag: String,
+    /// Checksum the part was uploaded with, see `Bucket::with_checksum_algorithm`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<ChecksumValue>,
+}
+
+/// Progress of a multipart upload, serializable so an interrupted upload can be continued with
+/// `Bucket::resume_multipart_upload`, even from another process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultipartUploadState {
+    /// Key of the object being uploaded.
+    pub path: String,
+    pub upload_id: String,
+    /// Size of every part but the last one.
+    pub part_size: usize,
+    /// Parts uploaded so far.
+    pub parts: Vec<Part>,
+}
+
+impl MultipartUploadState {
+    /// State of the upload `upload_id` to `path`, in parts of `part_size` bytes, at least the
+    /// 5 MiB S3 takes.
+    pub fn new(path: &str, upload_id: &str, part_size: usize) -> Result<Self, S3Error> {
+        MultipartUploadState::check_part_size(part_size)?;
+        Ok(MultipartUploadState {
+            path: path.to_string(),
+            upload_id: upload_id.to_string(),
+            part_size,
+            parts: Vec::new(),
+        })
+    }
+
+    /// Parts smaller than 5 MiB are refused by S3, and no progress is made with empty ones.
+    pub(crate) fn check_part_size(part_size: usize) -> Result<(), S3Error> {
+        if part_size < crate::bucket::MIN_PART_SIZE {
+            return Err(S3Error::InvalidArgument(format!(
+                "part_size needs to be at least {}, got {}",
+                crate::bucket::MIN_PART_SIZE,
+                part_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// Number of parts uploaded without a gap from the first one, an upload resumes after them.
+    pub fn completed_parts(&self) -> u32 {
+        let mut part_numbers = self
+            .parts
+            .iter()
+            .map(|part| part.part_number)
+            .collect::<Vec<_>>();
+        part_numbers.sort_unstable();
+        part_numbers.dedup();
+        part_numbers
+            .iter()
+            .zip(1..)
+            .take_while(|(part_number, expected)| **part_number == *expected)
+            .count() as u32
+    }
+
+    /// Offset in the source the upload resumes at, the reader handed to
+    /// `Bucket::resume_multipart_upload` has to be positioned there.
+    pub fn resume_offset(&self) -> u64 {
+        self.completed_parts() as u64 * self.part_size as u64
+    }
+
+    /// Drop the parts after the first gap, they are uploaded again on resume.
+    pub(crate) fn truncate_to_completed(&mut self) {
+        let completed = self.completed_parts();
+        self.parts.sort_by_key(|part| part.part_number);
+        self.parts.dedup_by_key(|part| part.part_number);
+        self.parts.retain(|part| part.part_number <= completed);
+    }
 }
 
 impl fmt::Display for Part {
@@ -107,13 +224,102 @@ impl fmt::Display for Part {
         write!(f, "<Part>").expect("Can't fail");
         write!(f, "<PartNumber>{}</PartNumber>", self.part_number).expect("Can't fail");
         write!(f, "<ETag>{}</ETag>", self.etag).expect("Can't fail");
+        if let Some(checksum) = &self.checksum {
+            let element = checksum.algorithm.xml_element();
+            write!(f, "<{}>{}</{}>", element, checksum.value, element).expect("Can't fail");
+        }
         write!(f, "</Part>")
     }
 }
 
+/// Body of a multi-object `DeleteObjects` request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename = "Delete")]
+pub struct DeleteObjectsData {
+    #[serde(rename = "Quiet")]
+    /// Only report keys that failed to delete.
+    pub quiet: bool,
+    #[serde(rename = "Object")]
+    /// Objects to delete, at most 1000 per request.
+    pub objects: Vec<ObjectIdentifier>,
+}
+
+impl fmt::Display for DeleteObjectsData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let xml = quick_xml::se::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", xml)
+    }
+}
+
+impl DeleteObjectsData {
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+/// A key, and optionally a version, identifying an object to delete
+#[derive(Debug, Clone, Serialize)]
+pub struct ObjectIdentifier {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "VersionId", skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+}
+
+impl ObjectIdentifier {
+    pub fn new(key: impl Into<String>) -> Self {
+        ObjectIdentifier {
+            key: key.into(),
+            version_id: None,
+        }
+    }
+}
+
+/// The parsed result of a multi-object `DeleteObjects` request
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct DeleteObjectsResult {
+    #[serde(rename = "Deleted", default)]
+    /// Objects that were successfully deleted.
+    pub deleted: Vec<DeletedObject>,
+    #[serde(rename = "Error", default)]
+    /// Objects that could not be deleted.
+    pub errors: Vec<DeleteObjectError>,
+}
+
+/// An object that was deleted by a `DeleteObjects` request
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeletedObject {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "VersionId")]
+    pub version_id: Option<String>,
+    #[serde(rename = "DeleteMarker")]
+    pub delete_marker: Option<bool>,
+    #[serde(rename = "DeleteMarkerVersionId")]
+    pub delete_marker_version_id: Option<String>,
+}
+
+/// An object that could not be deleted by a `DeleteObjects` request
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeleteObjectError {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "VersionId")]
+    pub version_id: Option<String>,
+    #[serde(rename = "Code")]
+    pub code: String,
+    #[serde(rename = "Message")]
+    pub message: String,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct BucketLocationResult {
-    #[serde(rename = "$value")]
+    /// Empty for us-east-1
+    #[serde(rename = "$value", default)]
     pub region: String,
 }
 
@@ -168,6 +374,14 @@ pub struct ListBucketResult {
     pub common_prefixes: Option<Vec<CommonPrefix>>,
 }
 
+impl ListBucketResult {
+    /// The common prefixes of a listing with a delimiter, the "folders" of the page, empty
+    /// when there are none.
+    pub fn prefixes(&self) -> &[CommonPrefix] {
+        self.common_prefixes.as_deref().unwrap_or_default()
+    }
+}
+
 /// The parsed result of a s3 bucket listing of uploads
 #[derive(Deserialize, Debug, Clone)]
 pub struct ListMultipartUploadsResult {
@@ -187,6 +401,13 @@ pub struct ListMultipartUploadsResult {
     #[serde(rename = "KeyMarker")]
     /// Indicates where in the bucket listing begins.
     pub marker: Option<String>,
+    #[serde(rename = "NextUploadIdMarker")]
+    /// Together with `next_marker`, the upload to continue the listing after when the
+    /// response is truncated.
+    pub next_upload_id_marker: Option<String>,
+    #[serde(rename = "UploadIdMarker")]
+    /// Together with `marker`, indicates where in the upload listing begins.
+    pub upload_id_marker: Option<String>,
     #[serde(rename = "EncodingType")]
     /// Specifies the encoding method to used
     pub encoding_type: Option<String>,
@@ -207,8 +428,265 @@ pub struct ListMultipartUploadsResult {
     pub common_prefixes: Option<Vec<CommonPrefix>>,
 }
 
-/// `CommonPrefix` is used to group keys
+/// The parsed result of listing the parts of a multipart upload
+#[derive(Deserialize, Debug, Clone)]
+pub struct ListPartsResult {
+    #[serde(rename = "Bucket")]
+    /// Name of the bucket.
+    pub bucket: String,
+    #[serde(rename = "Key")]
+    /// The object key of the upload.
+    pub key: String,
+    #[serde(rename = "UploadId")]
+    /// The identifier of the upload.
+    pub upload_id: String,
+    #[serde(rename = "StorageClass")]
+    /// Storage class the object will be stored with once the upload completes.
+    pub storage_class: Option<String>,
+    #[serde(rename = "PartNumberMarker")]
+    /// Indicates where in the part listing begins.
+    pub part_number_marker: Option<u32>,
+    #[serde(rename = "NextPartNumberMarker")]
+    /// When the response is truncated, use this as `part_number_marker` in the subsequent
+    /// request to get the next set of parts.
+    pub next_part_number_marker: Option<u32>,
+    #[serde(rename = "MaxParts")]
+    /// Maximum number of parts that were allowed in the response.
+    pub max_parts: Option<u32>,
+    #[serde(
+        rename = "IsTruncated",
+        deserialize_with = "super::deserializer::bool_deserializer"
+    )]
+    /// Specifies whether (true) or not (false) all of the parts were returned.
+    pub is_truncated: bool,
+    #[serde(rename = "Part", default)]
+    /// The parts uploaded so far.
+    pub parts: Vec<UploadedPart>,
+    #[serde(rename = "Owner")]
+    /// Owner of the object the upload will create.
+    pub owner: Option<Owner>,
+}
+
+/// An individual part in a `ListPartsResult`
+#[derive(Deserialize, Debug, Clone)]
+pub struct UploadedPart {
+    #[serde(rename = "PartNumber")]
+    /// Number identifying the part, between 1 and 10000.
+    pub part_number: u32,
+    #[serde(rename = "LastModified")]
+    /// Date and time the part was uploaded.
+    pub last_modified: String,
+    #[serde(rename = "ETag")]
+    /// Entity tag returned when the part was uploaded.
+    pub etag: String,
+    #[serde(rename = "Size")]
+    /// Size in bytes of the part.
+    pub size: u64,
+    #[serde(rename = "ChecksumCRC32")]
+    pub checksum_crc32: Option<String>,
+    #[serde(rename = "ChecksumCRC32C")]
+    pub checksum_crc32c: Option<String>,
+    #[serde(rename = "ChecksumSHA1")]
+    pub checksum_sha1: Option<String>,
+    #[serde(rename = "ChecksumSHA256")]
+    pub checksum_sha256: Option<String>,
+}
+
+impl UploadedPart {
+    /// Checksum the part was uploaded with, if any.
+    pub fn checksum(&self) -> Option<ChecksumValue> {
+        let checksums = [
+            (ChecksumAlgorithm::Crc32, &self.checksum_crc32),
+            (ChecksumAlgorithm::Crc32c, &self.checksum_crc32c),
+            (ChecksumAlgorithm::Sha1, &self.checksum_sha1),
+            (ChecksumAlgorithm::Sha256, &self.checksum_sha256),
+        ];
+        checksums.iter().find_map(|(algorithm, value)| {
+            value.as_ref().map(|value| ChecksumValue {
+                algorithm: *algorithm,
+                value: value.clone(),
+            })
+        })
+    }
+}
+
+impl From<UploadedPart> for Part {
+    fn from(part: UploadedPart) -> Self {
+        Part {
+            checksum: part.checksum(),
+            part_number: part.part_number,
+            etag: part.etag,
+        }
+    }
+}
+
+/// An attribute to request with `GetObjectAttributes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectAttribute {
+    ETag,
+    Checksum,
+    ObjectParts,
+    StorageClass,
+    ObjectSize,
+}
+
+impl ObjectAttribute {
+    /// Every attribute S3 knows about.
+    pub const ALL: [ObjectAttribute; 5] = [
+        ObjectAttribute::ETag,
+        ObjectAttribute::Checksum,
+        ObjectAttribute::ObjectParts,
+        ObjectAttribute::StorageClass,
+        ObjectAttribute::ObjectSize,
+    ];
+}
+
+impl fmt::Display for ObjectAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectAttribute::ETag => write!(f, "ETag"),
+            ObjectAttribute::Checksum => write!(f, "Checksum"),
+            ObjectAttribute::ObjectParts => write!(f, "ObjectParts"),
+            ObjectAttribute::StorageClass => write!(f, "StorageClass"),
+            ObjectAttribute::ObjectSize => write!(f, "ObjectSize"),
+        }
+    }
+}
+
+/// [AWS Documentation](https://docs.aws.amazon.com/AmazonS3/latest/userguide/storage-class-intro.html)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageClass {
+    Standard,
+    ReducedRedundancy,
+    StandardIa,
+    OnezoneIa,
+    IntelligentTiering,
+    Glacier,
+    GlacierIr,
+    DeepArchive,
+    Outposts,
+    /// Classes of S3 compatible stores, sent as is.
+    Custom(String),
+}
+
+impl fmt::Display for StorageClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageClass::Standard => write!(f, "STANDARD"),
+            StorageClass::ReducedRedundancy => write!(f, "REDUCED_REDUNDANCY"),
+            StorageClass::StandardIa => write!(f, "STANDARD_IA"),
+            StorageClass::OnezoneIa => write!(f, "ONEZONE_IA"),
+            StorageClass::IntelligentTiering => write!(f, "INTELLIGENT_TIERING"),
+            StorageClass::Glacier => write!(f, "GLACIER"),
+            StorageClass::GlacierIr => write!(f, "GLACIER_IR"),
+            StorageClass::DeepArchive => write!(f, "DEEP_ARCHIVE"),
+            StorageClass::Outposts => write!(f, "OUTPOSTS"),
+            StorageClass::Custom(class) => write!(f, "{}", class),
+        }
+    }
+}
+
+/// Parses the `StorageClass` strings of listings and `x-amz-storage-class` headers.
+impl std::str::FromStr for StorageClass {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "STANDARD" => StorageClass::Standard,
+            "REDUCED_REDUNDANCY" => StorageClass::ReducedRedundancy,
+            "STANDARD_IA" => StorageClass::StandardIa,
+            "ONEZONE_IA" => StorageClass::OnezoneIa,
+            "INTELLIGENT_TIERING" => StorageClass::IntelligentTiering,
+            "GLACIER" => StorageClass::Glacier,
+            "GLACIER_IR" => StorageClass::GlacierIr,
+            "DEEP_ARCHIVE" => StorageClass::DeepArchive,
+            "OUTPOSTS" => StorageClass::Outposts,
+            other => StorageClass::Custom(other.to_string()),
+        })
+    }
+}
+
+/// The parsed result of `GetObjectAttributes`, only requested attributes are set
+#[derive(Deserialize, Debug, Clone)]
+pub struct GetObjectAttributesResult {
+    #[serde(rename = "ETag")]
+    /// Entity tag of the object, without the surrounding quotes.
+    pub etag: Option<String>,
+    #[serde(rename = "Checksum")]
+    /// Checksum of the whole object, if one was stored at upload time.
+    pub checksum: Option<Checksum>,
+    #[serde(rename = "ObjectParts")]
+    /// Parts of an object uploaded with a multipart upload.
+    pub object_parts: Option<ObjectParts>,
+    #[serde(rename = "StorageClass")]
+    /// STANDARD | STANDARD_IA | REDUCED_REDUNDANCY | GLACIER
+    pub storage_class: Option<String>,
+    #[serde(rename = "ObjectSize")]
+    /// Size in bytes of the object.
+    pub object_size: Option<u64>,
+}
+
+/// Base64 encoded checksums, at most one of them is set
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Checksum {
+    #[serde(rename = "ChecksumCRC32")]
+    pub crc32: Option<String>,
+    #[serde(rename = "ChecksumCRC32C")]
+    pub crc32c: Option<String>,
+    #[serde(rename = "ChecksumSHA1")]
+    pub sha1: Option<String>,
+    #[serde(rename = "ChecksumSHA256")]
+    pub sha256: Option<String>,
+}
+
+/// Part listing of a `GetObjectAttributesResult`
+#[derive(Deserialize, Debug, Clone)]
+pub struct ObjectParts {
+    #[serde(rename = "PartsCount")]
+    /// Total number of parts of the object.
+    pub parts_count: Option<u32>,
+    #[serde(rename = "PartNumberMarker")]
+    /// Indicates where in the part listing begins.
+    pub part_number_marker: Option<u32>,
+    #[serde(rename = "NextPartNumberMarker")]
+    /// When the listing is truncated, use this as part number marker to get the next parts.
+    pub next_part_number_marker: Option<u32>,
+    #[serde(rename = "MaxParts")]
+    /// Maximum number of parts that were allowed in the response.
+    pub max_parts: Option<u32>,
+    #[serde(
+        rename = "IsTruncated",
+        default,
+        deserialize_with = "super::deserializer::bool_deserializer"
+    )]
+    /// Specifies whether (true) or not (false) all of the parts were returned.
+    pub is_truncated: bool,
+    #[serde(rename = "Part", default)]
+    /// The listed parts, only returned for objects uploaded with checksums.
+    pub parts: Vec<ObjectPart>,
+}
+
+/// A single part in `ObjectParts`
 #[derive(Deserialize, Debug, Clone)]
+pub struct ObjectPart {
+    #[serde(rename = "PartNumber")]
+    /// Number identifying the part, between 1 and 10000.
+    pub part_number: u32,
+    #[serde(rename = "Size")]
+    /// Size in bytes of the part.
+    pub size: u64,
+    #[serde(rename = "ChecksumCRC32")]
+    pub checksum_crc32: Option<String>,
+    #[serde(rename = "ChecksumCRC32C")]
+    pub checksum_crc32c: Option<String>,
+    #[serde(rename = "ChecksumSHA1")]
+    pub checksum_sha1: Option<String>,
+    #[serde(rename = "ChecksumSHA256")]
+    pub checksum_sha256: Option<String>,
+}
+
+/// `CommonPrefix` is used to group keys
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct CommonPrefix {
     #[serde(rename = "Prefix")]
     /// Keys that begin with the indicated prefix.
@@ -294,6 +772,9 @@ pub struct HeadObjectResult {
     #[serde(rename = "SsekmsKeyId")]
     /// If present, specifies the ID of the AWS Key Management Service (AWS KMS) symmetric customer managed customer master key (CMK) that was used for the object.
     pub ssekms_key_id: Option<String>,
+    #[serde(rename = "BucketKeyEnabled")]
+    /// Whether the object was encrypted with an S3 Bucket Key for SSE-KMS.
+    pub bucket_key_enabled: Option<bool>,
     #[serde(rename = "ServerSideEncryption")]
     /// If the object is stored using server-side encryption either with an AWS KMS customer master key (CMK) or an Amazon S3-managed encryption key,
     /// The response includes this header with the value of the server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).
@@ -309,6 +790,202 @@ pub struct HeadObjectResult {
     pub website_redirect_location: Option<String>,
 }
 
+impl HeadObjectResult {
+    /// `last_modified` parsed as a timestamp, `None` if missing or malformed.
+    pub fn last_modified_at(&self) -> Option<OffsetDateTime> {
+        parse_http_date(self.last_modified.as_deref()?)
+    }
+
+    /// Parsed `x-amz-restore` header, `None` if no restore was ever requested.
+    pub fn restore_status(&self) -> Option<RestoreStatus> {
+        self.restore.as_deref().map(RestoreStatus::parse)
+    }
+}
+
+/// Server side encryption S3 applied to a stored object
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectEncryption {
+    /// `AES256`, `aws:kms` or `aws:kms:dsse`, `None` for customer provided keys.
+    pub algorithm: Option<String>,
+    pub kms_key_id: Option<String>,
+    pub bucket_key_enabled: Option<bool>,
+    /// Algorithm of the customer provided key (SSE-C).
+    pub customer_algorithm: Option<String>,
+    pub customer_key_md5: Option<String>,
+}
+
+/// Outcome of a `PutObject`, from the headers of the response
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PutObjectResponse {
+    /// ETag of the stored object, quotes included.
+    pub etag: Option<String>,
+    /// Version of the object, in versioned buckets only.
+    pub version_id: Option<String>,
+    /// `None` for objects stored unencrypted.
+    pub sse: Option<ObjectEncryption>,
+    /// Checksum S3 verified, for uploads with `Bucket::with_checksum_algorithm`.
+    pub checksum: Option<ChecksumValue>,
+    pub status_code: u16,
+}
+
+impl PutObjectResponse {
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+}
+
+/// Body of a `GetObject` together with the object metadata S3 sent along
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetObjectResponse {
+    pub body: bytes::Bytes,
+    pub content_type: Option<String>,
+    /// Length of `body`, of the requested range only for ranged requests.
+    pub content_length: Option<u64>,
+    pub last_modified: Option<OffsetDateTime>,
+    /// ETag of the object, quotes included.
+    pub etag: Option<String>,
+    /// Version of the object, in versioned buckets only.
+    pub version_id: Option<String>,
+    /// User metadata, the `x-amz-meta-*` headers with the prefix stripped.
+    pub metadata: ::std::collections::HashMap<String, String>,
+    pub status_code: u16,
+}
+
+impl GetObjectResponse {
+    pub fn as_slice(&self) -> &[u8] {
+        &self.body
+    }
+
+    pub fn to_vec(self) -> Vec<u8> {
+        self.body.to_vec()
+    }
+
+    pub fn as_str(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.body)
+    }
+
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+}
+
+/// Preconditions of a conditional `GetObject` or `HeadObject`, S3 answers `304 Not Modified`
+/// without a body when they fail
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectConditions {
+    /// ETag, quotes included, of the copy the caller already has.
+    pub if_none_match: Option<String>,
+    pub if_modified_since: Option<OffsetDateTime>,
+}
+
+impl ObjectConditions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn if_none_match<S: Into<String>>(mut self, etag: S) -> Self {
+        self.if_none_match = Some(etag.into());
+        self
+    }
+
+    pub fn if_modified_since(mut self, date: OffsetDateTime) -> Self {
+        self.if_modified_since = Some(date);
+        self
+    }
+
+    pub fn add_headers(&self, headers: &mut HeaderMap) -> Result<(), S3Error> {
+        if let Some(etag) = &self.if_none_match {
+            headers.insert(IF_NONE_MATCH, etag.parse()?);
+        }
+        if let Some(date) = self.if_modified_since {
+            let date = date.to_offset(time::UtcOffset::UTC).format(HTTP_DATE)?;
+            headers.insert(IF_MODIFIED_SINCE, date.parse()?);
+        }
+        Ok(())
+    }
+}
+
+/// Parsed `Content-Range` header of a ranged `GetObject` response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentRange {
+    /// First byte of the returned part, inclusive.
+    pub start: u64,
+    /// Last byte of the returned part, inclusive.
+    pub end: u64,
+    /// Size of the whole object, `None` if the server didn't know it (`*`).
+    pub total: Option<u64>,
+}
+
+impl ContentRange {
+    /// Parse a header like `bytes 0-1023/146515`, `None` if malformed or unsatisfied
+    /// (`bytes */146515`).
+    pub fn parse(header: &str) -> Option<Self> {
+        let (range, total) = header.trim().strip_prefix("bytes ")?.split_once('/')?;
+        let (start, end) = range.split_once('-')?;
+        let total = match total {
+            "*" => None,
+            total => Some(total.parse().ok()?),
+        };
+        Some(ContentRange {
+            start: start.parse().ok()?,
+            end: end.parse().ok()?,
+            total,
+        })
+    }
+
+    /// Number of bytes in the returned part.
+    pub fn length(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// Result of a conditional request
+#[derive(Debug, Clone)]
+pub enum Conditional<T> {
+    /// The preconditions held, `T` is the regular response.
+    Modified(T),
+    /// `304 Not Modified`, the caller's copy is up to date.
+    NotModified,
+}
+
+impl<T> Conditional<T> {
+    pub fn is_modified(&self) -> bool {
+        matches!(self, Conditional::Modified(_))
+    }
+
+    /// The response, `None` if the object was not modified.
+    pub fn modified(self) -> Option<T> {
+        match self {
+            Conditional::Modified(response) => Some(response),
+            Conditional::NotModified => None,
+        }
+    }
+}
+
+/// Progress of a `RestoreObject` request, as reported by `x-amz-restore`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreStatus {
+    /// `true` while the object is still being restored.
+    pub ongoing_request: bool,
+    /// When the restored copy will be removed again, once the restore completed.
+    pub expiry_date: Option<String>,
+}
+
+impl RestoreStatus {
+    /// Parse a header like `ongoing-request="false", expiry-date="Fri, 21 Dec 2012 00:00:00 GMT"`.
+    pub fn parse(header: &str) -> Self {
+        let value_of = |key: &str| {
+            let start = header.find(key)? + key.len();
+            let rest = header[start..].strip_prefix("=\"")?;
+            rest.find('"').map(|end| rest[..end].to_string())
+        };
+        RestoreStatus {
+            ongoing_request: value_of("ongoing-request").as_deref() == Some("true"),
+            expiry_date: value_of("expiry-date"),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct AwsError {
     #[serde(rename = "Code")]
@@ -323,32 +1000,48 @@ pub struct AwsError {
 #[serde(rename = "CORSConfiguration")]
 pub struct CorsConfiguration {
     #[serde(rename = "CORSRule")]
-    rules: Vec<CorsRule>,
+    pub rules: Vec<CorsRule>,
 }
 
 impl CorsConfiguration {
     pub fn new(rules: Vec<CorsRule>) -> Self {
         CorsConfiguration { rules }
     }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for CorsConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let xml = quick_xml::se::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", xml)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CorsRule {
     #[serde(rename = "AllowedHeader")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    allowed_headers: Option<Vec<String>>,
+    pub allowed_headers: Option<Vec<String>>,
     #[serde(rename = "AllowedMethod")]
-    allowed_methods: Vec<String>,
+    pub allowed_methods: Vec<String>,
     #[serde(rename = "AllowedOrigin")]
-    allowed_origins: Vec<String>,
+    pub allowed_origins: Vec<String>,
     #[serde(rename = "ExposeHeader")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    expose_headers: Option<Vec<String>>,
+    pub expose_headers: Option<Vec<String>>,
     #[serde(rename = "ID")]
-    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "MaxAgeSeconds")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    max_age_seconds: Option<u32>,
+    pub max_age_seconds: Option<u32>,
 }
 
 impl CorsRule {
@@ -369,15 +1062,1504 @@ impl CorsRule {
             max_age_seconds,
         }
     }
+
+    pub fn builder() -> CorsRuleBuilder {
+        CorsRuleBuilder::default()
+    }
 }
 
-#[cfg(test)]
-mod test {
-    use super::{CorsConfiguration, CorsRule};
+/// Builds a `CorsRule` one allowed origin, method or header at a time
+///
+/// ```
+/// use s3::command::HttpMethod;
+/// use s3::serde_types::CorsRule;
+///
+/// let rule = CorsRule::builder()
+///     .allowed_origin("https://example.com")
+///     .allowed_method(HttpMethod::Get)
+///     .allowed_method(HttpMethod::Head)
+///     .allowed_header("*")
+///     .max_age_seconds(3600)
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CorsRuleBuilder {
+    allowed_headers: Vec<String>,
+    allowed_methods: Vec<String>,
+    allowed_origins: Vec<String>,
+    expose_headers: Vec<String>,
+    id: Option<String>,
+    max_age_seconds: Option<u32>,
+}
 
-    #[test]
-    fn cors_config_serde() {
-        let rule = CorsRule {
+impl CorsRuleBuilder {
+    pub fn allowed_origin(mut self, origin: impl Into<String>) -> Self {
+        self.allowed_origins.push(origin.into());
+        self
+    }
+
+    pub fn allowed_method(mut self, method: HttpMethod) -> Self {
+        self.allowed_methods.push(method.to_string());
+        self
+    }
+
+    pub fn allowed_header(mut self, header: impl Into<String>) -> Self {
+        self.allowed_headers.push(header.into());
+        self
+    }
+
+    pub fn expose_header(mut self, header: impl Into<String>) -> Self {
+        self.expose_headers.push(header.into());
+        self
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn max_age_seconds(mut self, max_age_seconds: u32) -> Self {
+        self.max_age_seconds = Some(max_age_seconds);
+        self
+    }
+
+    pub fn build(self) -> CorsRule {
+        CorsRule {
+            allowed_headers: Some(self.allowed_headers).filter(|h| !h.is_empty()),
+            allowed_methods: self.allowed_methods,
+            allowed_origins: self.allowed_origins,
+            expose_headers: Some(self.expose_headers).filter(|h| !h.is_empty()),
+            id: self.id,
+            max_age_seconds: self.max_age_seconds,
+        }
+    }
+}
+
+/// The unit variant named by the text of an element. quick-xml would otherwise take the element
+/// name for the variant, `<Enabled/>` instead of `<Status>Enabled</Status>`.
+fn variant_from_text<'de, D, T>(deserializer: D, variants: &[(&str, T)]) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Copy,
+{
+    let text = String::deserialize(deserializer)?;
+    variants
+        .iter()
+        .find(|(name, _)| *name == text)
+        .map(|(_, variant)| *variant)
+        .ok_or_else(|| de::Error::custom(format!("unknown variant `{}`", text)))
+}
+
+/// Versioning state of a bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersioningStatus {
+    Enabled,
+    Suspended,
+}
+
+impl fmt::Display for VersioningStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersioningStatus::Enabled => write!(f, "Enabled"),
+            VersioningStatus::Suspended => write!(f, "Suspended"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VersioningStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        variant_from_text(
+            deserializer,
+            &[
+                ("Enabled", VersioningStatus::Enabled),
+                ("Suspended", VersioningStatus::Suspended),
+            ],
+        )
+    }
+}
+
+/// Whether deleting object versions requires multi-factor authentication
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MfaDelete {
+    Enabled,
+    Disabled,
+}
+
+impl fmt::Display for MfaDelete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MfaDelete::Enabled => write!(f, "Enabled"),
+            MfaDelete::Disabled => write!(f, "Disabled"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MfaDelete {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        variant_from_text(
+            deserializer,
+            &[
+                ("Enabled", MfaDelete::Enabled),
+                ("Disabled", MfaDelete::Disabled),
+            ],
+        )
+    }
+}
+
+/// The versioning configuration of a bucket
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct VersioningConfiguration {
+    #[serde(rename = "Status")]
+    /// `None` if versioning has never been enabled on the bucket.
+    pub status: Option<VersioningStatus>,
+    #[serde(rename = "MfaDelete")]
+    /// Only returned if MFA delete has been configured on the bucket.
+    pub mfa_delete: Option<MfaDelete>,
+}
+
+impl VersioningConfiguration {
+    pub fn new(status: VersioningStatus) -> Self {
+        VersioningConfiguration {
+            status: Some(status),
+            mfa_delete: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for VersioningConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<VersioningConfiguration>")?;
+        if let Some(status) = self.status {
+            write!(f, "<Status>{}</Status>", status)?;
+        }
+        if let Some(mfa_delete) = self.mfa_delete {
+            write!(f, "<MfaDelete>{}</MfaDelete>", mfa_delete)?;
+        }
+        write!(f, "</VersioningConfiguration>")
+    }
+}
+
+/// Who pays for requests and downloads of a bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Payer {
+    Requester,
+    BucketOwner,
+}
+
+impl fmt::Display for Payer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Payer::Requester => write!(f, "Requester"),
+            Payer::BucketOwner => write!(f, "BucketOwner"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Payer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        variant_from_text(
+            deserializer,
+            &[
+                ("Requester", Payer::Requester),
+                ("BucketOwner", Payer::BucketOwner),
+            ],
+        )
+    }
+}
+
+/// The request payment configuration of a bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct RequestPaymentConfiguration {
+    #[serde(rename = "Payer")]
+    pub payer: Payer,
+}
+
+impl RequestPaymentConfiguration {
+    pub fn new(payer: Payer) -> Self {
+        RequestPaymentConfiguration { payer }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for RequestPaymentConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<RequestPaymentConfiguration><Payer>{}</Payer></RequestPaymentConfiguration>",
+            self.payer
+        )
+    }
+}
+
+/// Retention mode of a locked object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetentionMode {
+    Governance,
+    Compliance,
+}
+
+impl fmt::Display for RetentionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetentionMode::Governance => write!(f, "GOVERNANCE"),
+            RetentionMode::Compliance => write!(f, "COMPLIANCE"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RetentionMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        variant_from_text(
+            deserializer,
+            &[
+                ("GOVERNANCE", RetentionMode::Governance),
+                ("COMPLIANCE", RetentionMode::Compliance),
+            ],
+        )
+    }
+}
+
+/// The Object Lock configuration of a bucket
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ObjectLockConfiguration {
+    #[serde(rename = "ObjectLockEnabled")]
+    /// `Enabled` if Object Lock is enabled on the bucket.
+    pub object_lock_enabled: Option<String>,
+    #[serde(rename = "Rule")]
+    /// Retention applied to new objects that don't specify their own.
+    pub rule: Option<ObjectLockRule>,
+}
+
+impl ObjectLockConfiguration {
+    pub fn new(default_retention: DefaultRetention) -> Self {
+        ObjectLockConfiguration {
+            object_lock_enabled: Some("Enabled".to_string()),
+            rule: Some(ObjectLockRule { default_retention }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for ObjectLockConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<ObjectLockConfiguration>")?;
+        if let Some(ref enabled) = self.object_lock_enabled {
+            write!(f, "<ObjectLockEnabled>{}</ObjectLockEnabled>", enabled)?;
+        }
+        if let Some(ref rule) = self.rule {
+            let retention = &rule.default_retention;
+            write!(f, "<Rule><DefaultRetention>")?;
+            write!(f, "<Mode>{}</Mode>", retention.mode)?;
+            if let Some(days) = retention.days {
+                write!(f, "<Days>{}</Days>", days)?;
+            }
+            if let Some(years) = retention.years {
+                write!(f, "<Years>{}</Years>", years)?;
+            }
+            write!(f, "</DefaultRetention></Rule>")?;
+        }
+        write!(f, "</ObjectLockConfiguration>")
+    }
+}
+
+/// Default retention rule of an `ObjectLockConfiguration`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ObjectLockRule {
+    #[serde(rename = "DefaultRetention")]
+    pub default_retention: DefaultRetention,
+}
+
+/// Default retention period of a bucket, exactly one of `days` or `years` must be set
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DefaultRetention {
+    #[serde(rename = "Mode")]
+    pub mode: RetentionMode,
+    #[serde(rename = "Days")]
+    pub days: Option<u32>,
+    #[serde(rename = "Years")]
+    pub years: Option<u32>,
+}
+
+impl DefaultRetention {
+    pub fn days(mode: RetentionMode, days: u32) -> Self {
+        DefaultRetention {
+            mode,
+            days: Some(days),
+            years: None,
+        }
+    }
+
+    pub fn years(mode: RetentionMode, years: u32) -> Self {
+        DefaultRetention {
+            mode,
+            days: None,
+            years: Some(years),
+        }
+    }
+}
+
+/// Retention settings of a single object version
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ObjectRetention {
+    #[serde(rename = "Mode")]
+    pub mode: Option<RetentionMode>,
+    #[serde(rename = "RetainUntilDate")]
+    /// ISO 8601 timestamp until which the object can't be overwritten or deleted.
+    pub retain_until_date: Option<String>,
+}
+
+impl ObjectRetention {
+    pub fn new(mode: RetentionMode, retain_until_date: impl Into<String>) -> Self {
+        ObjectRetention {
+            mode: Some(mode),
+            retain_until_date: Some(retain_until_date.into()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for ObjectRetention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Retention>")?;
+        if let Some(mode) = self.mode {
+            write!(f, "<Mode>{}</Mode>", mode)?;
+        }
+        if let Some(ref retain_until_date) = self.retain_until_date {
+            write!(
+                f,
+                "<RetainUntilDate>{}</RetainUntilDate>",
+                retain_until_date
+            )?;
+        }
+        write!(f, "</Retention>")
+    }
+}
+
+/// Legal hold status of a single object version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegalHoldStatus {
+    On,
+    Off,
+}
+
+impl fmt::Display for LegalHoldStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LegalHoldStatus::On => write!(f, "ON"),
+            LegalHoldStatus::Off => write!(f, "OFF"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LegalHoldStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        variant_from_text(
+            deserializer,
+            &[("ON", LegalHoldStatus::On), ("OFF", LegalHoldStatus::Off)],
+        )
+    }
+}
+
+/// The legal hold of a single object version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct ObjectLegalHold {
+    #[serde(rename = "Status")]
+    pub status: LegalHoldStatus,
+}
+
+impl ObjectLegalHold {
+    pub fn new(status: LegalHoldStatus) -> Self {
+        ObjectLegalHold { status }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for ObjectLegalHold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<LegalHold><Status>{}</Status></LegalHold>", self.status)
+    }
+}
+
+/// The owner and grants of an object or bucket ACL
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccessControlPolicy {
+    #[serde(rename = "Owner")]
+    pub owner: Owner,
+    #[serde(rename = "AccessControlList")]
+    pub access_control_list: AccessControlList,
+}
+
+impl AccessControlPolicy {
+    pub fn new(owner: Owner, grants: Vec<Grant>) -> Self {
+        AccessControlPolicy {
+            owner,
+            access_control_list: AccessControlList { grants },
+        }
+    }
+}
+
+impl fmt::Display for AccessControlPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<AccessControlPolicy><Owner>")?;
+        write!(f, "<ID>{}</ID>", escape(&self.owner.id))?;
+        if let Some(ref display_name) = self.owner.display_name {
+            write!(f, "<DisplayName>{}</DisplayName>", escape(display_name))?;
+        }
+        write!(f, "</Owner><AccessControlList>")?;
+        for grant in &self.access_control_list.grants {
+            write!(f, "{}", grant)?;
+        }
+        write!(f, "</AccessControlList></AccessControlPolicy>")
+    }
+}
+
+/// The grants of an `AccessControlPolicy`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AccessControlList {
+    #[serde(rename = "Grant", default)]
+    pub grants: Vec<Grant>,
+}
+
+/// A permission given to a grantee
+#[derive(Debug, Clone, Deserialize)]
+pub struct Grant {
+    #[serde(rename = "Grantee")]
+    pub grantee: Grantee,
+    #[serde(rename = "Permission")]
+    pub permission: Permission,
+}
+
+impl Grant {
+    pub fn new(grantee: Grantee, permission: Permission) -> Self {
+        Grant {
+            grantee,
+            permission,
+        }
+    }
+}
+
+impl fmt::Display for Grant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<Grant>{}<Permission>{}</Permission></Grant>",
+            self.grantee, self.permission
+        )
+    }
+}
+
+/// The receiver of a `Grant`, identified by canonical user ID, email address or group URI
+#[derive(Debug, Clone, Deserialize)]
+pub struct Grantee {
+    #[serde(rename = "@type")]
+    /// CanonicalUser | AmazonCustomerByEmail | Group
+    pub grantee_type: String,
+    #[serde(rename = "ID")]
+    pub id: Option<String>,
+    #[serde(rename = "DisplayName")]
+    pub display_name: Option<String>,
+    #[serde(rename = "EmailAddress")]
+    pub email_address: Option<String>,
+    #[serde(rename = "URI")]
+    pub uri: Option<String>,
+}
+
+impl Grantee {
+    pub fn canonical_user(id: impl Into<String>) -> Self {
+        Grantee {
+            grantee_type: "CanonicalUser".to_string(),
+            id: Some(id.into()),
+            display_name: None,
+            email_address: None,
+            uri: None,
+        }
+    }
+
+    pub fn email(email_address: impl Into<String>) -> Self {
+        Grantee {
+            grantee_type: "AmazonCustomerByEmail".to_string(),
+            id: None,
+            display_name: None,
+            email_address: Some(email_address.into()),
+            uri: None,
+        }
+    }
+
+    pub fn group(uri: impl Into<String>) -> Self {
+        Grantee {
+            grantee_type: "Group".to_string(),
+            id: None,
+            display_name: None,
+            email_address: None,
+            uri: Some(uri.into()),
+        }
+    }
+
+    /// The group of all users, anonymous requests included.
+    pub fn all_users() -> Self {
+        Grantee::group("http://acs.amazonaws.com/groups/global/AllUsers")
+    }
+}
+
+impl fmt::Display for Grantee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<Grantee xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:type=\"{}\">",
+            escape(&self.grantee_type)
+        )?;
+        if let Some(ref id) = self.id {
+            write!(f, "<ID>{}</ID>", escape(id))?;
+        }
+        if let Some(ref display_name) = self.display_name {
+            write!(f, "<DisplayName>{}</DisplayName>", escape(display_name))?;
+        }
+        if let Some(ref email_address) = self.email_address {
+            write!(f, "<EmailAddress>{}</EmailAddress>", escape(email_address))?;
+        }
+        if let Some(ref uri) = self.uri {
+            write!(f, "<URI>{}</URI>", escape(uri))?;
+        }
+        write!(f, "</Grantee>")
+    }
+}
+
+/// [AWS Documentation](https://docs.aws.amazon.com/AmazonS3/latest/userguide/acl-overview.html#permissions)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    FullControl,
+    Write,
+    WriteAcp,
+    Read,
+    ReadAcp,
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Permission::FullControl => write!(f, "FULL_CONTROL"),
+            Permission::Write => write!(f, "WRITE"),
+            Permission::WriteAcp => write!(f, "WRITE_ACP"),
+            Permission::Read => write!(f, "READ"),
+            Permission::ReadAcp => write!(f, "READ_ACP"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Permission {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        variant_from_text(
+            deserializer,
+            &[
+                ("FULL_CONTROL", Permission::FullControl),
+                ("WRITE", Permission::Write),
+                ("WRITE_ACP", Permission::WriteAcp),
+                ("READ", Permission::Read),
+                ("READ_ACP", Permission::ReadAcp),
+            ],
+        )
+    }
+}
+
+/// The lifecycle rules of a bucket
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "LifecycleConfiguration")]
+pub struct LifecycleConfiguration {
+    #[serde(rename = "Rule", default)]
+    pub rules: Vec<LifecycleRule>,
+}
+
+impl LifecycleConfiguration {
+    pub fn new(rules: Vec<LifecycleRule>) -> Self {
+        LifecycleConfiguration { rules }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for LifecycleConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let xml = quick_xml::se::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", xml)
+    }
+}
+
+/// A single rule of a `LifecycleConfiguration`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LifecycleRule {
+    #[serde(rename = "Expiration", skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<LifecycleExpiration>,
+    #[serde(rename = "ID", skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "Filter", skip_serializing_if = "Option::is_none")]
+    /// Objects the rule applies to, the whole bucket if empty.
+    pub filter: Option<LifecycleFilter>,
+    #[serde(rename = "Status")]
+    pub status: LifecycleRuleStatus,
+    #[serde(rename = "Transition", default, skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<Transition>,
+    #[serde(
+        rename = "NoncurrentVersionExpiration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub noncurrent_version_expiration: Option<NoncurrentVersionExpiration>,
+    #[serde(
+        rename = "AbortIncompleteMultipartUpload",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub abort_incomplete_multipart_upload: Option<AbortIncompleteMultipartUpload>,
+}
+
+impl LifecycleRule {
+    /// An enabled rule without any actions, set at least one before applying it.
+    pub fn new(id: impl Into<String>, filter: LifecycleFilter) -> Self {
+        LifecycleRule {
+            expiration: None,
+            id: Some(id.into()),
+            filter: Some(filter),
+            status: LifecycleRuleStatus::Enabled,
+            transitions: Vec::new(),
+            noncurrent_version_expiration: None,
+            abort_incomplete_multipart_upload: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleRuleStatus {
+    Enabled,
+    Disabled,
+}
+
+impl fmt::Display for LifecycleRuleStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifecycleRuleStatus::Enabled => write!(f, "Enabled"),
+            LifecycleRuleStatus::Disabled => write!(f, "Disabled"),
+        }
+    }
+}
+
+impl Serialize for LifecycleRuleStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LifecycleRuleStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        variant_from_text(
+            deserializer,
+            &[
+                ("Enabled", LifecycleRuleStatus::Enabled),
+                ("Disabled", LifecycleRuleStatus::Disabled),
+            ],
+        )
+    }
+}
+
+/// Selects the objects a `LifecycleRule` applies to
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LifecycleFilter {
+    #[serde(rename = "Prefix", skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(rename = "Tag", skip_serializing_if = "Option::is_none")]
+    pub tag: Option<FilterTag>,
+    #[serde(rename = "And", skip_serializing_if = "Option::is_none")]
+    pub and: Option<LifecycleFilterAnd>,
+}
+
+impl LifecycleFilter {
+    pub fn prefix(prefix: impl Into<String>) -> Self {
+        LifecycleFilter {
+            prefix: Some(prefix.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn tag(key: impl Into<String>, value: impl Into<String>) -> Self {
+        LifecycleFilter {
+            tag: Some(FilterTag::new(key, value)),
+            ..Default::default()
+        }
+    }
+
+    /// Match objects under `prefix` that carry all of `tags`.
+    pub fn and(prefix: Option<String>, tags: Vec<FilterTag>) -> Self {
+        LifecycleFilter {
+            and: Some(LifecycleFilterAnd { prefix, tags }),
+            ..Default::default()
+        }
+    }
+}
+
+/// Combination of a prefix and several tags in a `LifecycleFilter`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LifecycleFilterAnd {
+    #[serde(rename = "Prefix", skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(rename = "Tag", default)]
+    pub tags: Vec<FilterTag>,
+}
+
+/// An object tag matched by a configuration filter
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterTag {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "Value")]
+    pub value: String,
+}
+
+impl FilterTag {
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        FilterTag {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// When objects matched by a `LifecycleRule` expire, either `date` or `days` must be set
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LifecycleExpiration {
+    #[serde(rename = "Date", skip_serializing_if = "Option::is_none")]
+    /// ISO 8601 date at midnight UTC.
+    pub date: Option<String>,
+    #[serde(rename = "Days", skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+    #[serde(
+        rename = "ExpiredObjectDeleteMarker",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expired_object_delete_marker: Option<bool>,
+}
+
+impl LifecycleExpiration {
+    pub fn days(days: u32) -> Self {
+        LifecycleExpiration {
+            days: Some(days),
+            ..Default::default()
+        }
+    }
+
+    pub fn date(date: impl Into<String>) -> Self {
+        LifecycleExpiration {
+            date: Some(date.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Moves objects matched by a `LifecycleRule` to another storage class
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Transition {
+    #[serde(rename = "Date", skip_serializing_if = "Option::is_none")]
+    /// ISO 8601 date at midnight UTC.
+    pub date: Option<String>,
+    #[serde(rename = "Days", skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+    #[serde(rename = "StorageClass")]
+    /// GLACIER | STANDARD_IA | ONEZONE_IA | INTELLIGENT_TIERING | DEEP_ARCHIVE | GLACIER_IR
+    pub storage_class: String,
+}
+
+impl Transition {
+    pub fn days(days: u32, storage_class: impl Into<String>) -> Self {
+        Transition {
+            date: None,
+            days: Some(days),
+            storage_class: storage_class.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NoncurrentVersionExpiration {
+    #[serde(rename = "NoncurrentDays")]
+    pub noncurrent_days: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AbortIncompleteMultipartUpload {
+    #[serde(rename = "DaysAfterInitiation")]
+    pub days_after_initiation: u32,
+}
+
+/// The replication rules of a bucket, versioning must be enabled on source and destination
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename = "ReplicationConfiguration")]
+pub struct ReplicationConfiguration {
+    #[serde(rename = "Role")]
+    /// ARN of the IAM role S3 assumes to replicate objects.
+    pub role: String,
+    #[serde(rename = "Rule", default)]
+    pub rules: Vec<ReplicationRule>,
+}
+
+impl ReplicationConfiguration {
+    pub fn new(role: impl Into<String>, rules: Vec<ReplicationRule>) -> Self {
+        ReplicationConfiguration {
+            role: role.into(),
+            rules,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for ReplicationConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let xml = quick_xml::se::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", xml)
+    }
+}
+
+/// A single rule of a `ReplicationConfiguration`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplicationRule {
+    #[serde(rename = "ID", skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "Priority", skip_serializing_if = "Option::is_none")]
+    /// Decides which rule wins when several rules match the same object, highest first.
+    pub priority: Option<u32>,
+    #[serde(rename = "Filter", skip_serializing_if = "Option::is_none")]
+    /// Objects the rule applies to, shaped like a lifecycle filter.
+    pub filter: Option<LifecycleFilter>,
+    #[serde(rename = "Status")]
+    pub status: ReplicationStatus,
+    #[serde(rename = "Destination")]
+    pub destination: ReplicationDestination,
+    #[serde(
+        rename = "DeleteMarkerReplication",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub delete_marker_replication: Option<DeleteMarkerReplication>,
+}
+
+impl ReplicationRule {
+    /// An enabled rule replicating the whole bucket, delete markers excluded.
+    pub fn new(id: impl Into<String>, destination_bucket_arn: impl Into<String>) -> Self {
+        ReplicationRule {
+            id: Some(id.into()),
+            priority: None,
+            filter: Some(LifecycleFilter::default()),
+            status: ReplicationStatus::Enabled,
+            destination: ReplicationDestination::new(destination_bucket_arn),
+            delete_marker_replication: Some(DeleteMarkerReplication {
+                status: ReplicationStatus::Disabled,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicationStatus {
+    Enabled,
+    Disabled,
+}
+
+impl fmt::Display for ReplicationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplicationStatus::Enabled => write!(f, "Enabled"),
+            ReplicationStatus::Disabled => write!(f, "Disabled"),
+        }
+    }
+}
+
+impl Serialize for ReplicationStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ReplicationStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        variant_from_text(
+            deserializer,
+            &[
+                ("Enabled", ReplicationStatus::Enabled),
+                ("Disabled", ReplicationStatus::Disabled),
+            ],
+        )
+    }
+}
+
+/// Where a `ReplicationRule` copies objects to
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplicationDestination {
+    #[serde(rename = "Bucket")]
+    /// ARN of the destination bucket, `arn:aws:s3:::bucket-name`.
+    pub bucket: String,
+    #[serde(rename = "Account", skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    #[serde(rename = "StorageClass", skip_serializing_if = "Option::is_none")]
+    /// Storage class of the replicas, that of the source object if `None`.
+    pub storage_class: Option<String>,
+}
+
+impl ReplicationDestination {
+    pub fn new(bucket_arn: impl Into<String>) -> Self {
+        ReplicationDestination {
+            bucket: bucket_arn.into(),
+            account: None,
+            storage_class: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeleteMarkerReplication {
+    #[serde(rename = "Status")]
+    pub status: ReplicationStatus,
+}
+
+/// The default encryption applied to new objects in a bucket
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename = "ServerSideEncryptionConfiguration")]
+pub struct ServerSideEncryptionConfiguration {
+    #[serde(rename = "Rule", default)]
+    pub rules: Vec<ServerSideEncryptionRule>,
+}
+
+impl ServerSideEncryptionConfiguration {
+    /// Encrypt with S3 managed keys.
+    pub fn aes256() -> Self {
+        ServerSideEncryptionConfiguration {
+            rules: vec![ServerSideEncryptionRule {
+                apply_server_side_encryption_by_default: ServerSideEncryptionByDefault {
+                    sse_algorithm: SseAlgorithm::Aes256,
+                    kms_master_key_id: None,
+                },
+                bucket_key_enabled: None,
+            }],
+        }
+    }
+
+    /// Encrypt with a KMS key, the AWS managed `aws/s3` key if `kms_master_key_id` is `None`.
+    pub fn kms(kms_master_key_id: Option<String>, bucket_key_enabled: bool) -> Self {
+        ServerSideEncryptionConfiguration {
+            rules: vec![ServerSideEncryptionRule {
+                apply_server_side_encryption_by_default: ServerSideEncryptionByDefault {
+                    sse_algorithm: SseAlgorithm::AwsKms,
+                    kms_master_key_id,
+                },
+                bucket_key_enabled: Some(bucket_key_enabled),
+            }],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for ServerSideEncryptionConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let xml = quick_xml::se::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", xml)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerSideEncryptionRule {
+    #[serde(rename = "ApplyServerSideEncryptionByDefault")]
+    pub apply_server_side_encryption_by_default: ServerSideEncryptionByDefault,
+    #[serde(rename = "BucketKeyEnabled", skip_serializing_if = "Option::is_none")]
+    /// Use an S3 Bucket Key to reduce the number of calls to KMS.
+    pub bucket_key_enabled: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerSideEncryptionByDefault {
+    #[serde(rename = "SSEAlgorithm")]
+    pub sse_algorithm: SseAlgorithm,
+    #[serde(rename = "KMSMasterKeyID", skip_serializing_if = "Option::is_none")]
+    pub kms_master_key_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SseAlgorithm {
+    Aes256,
+    AwsKms,
+    AwsKmsDsse,
+}
+
+impl fmt::Display for SseAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SseAlgorithm::Aes256 => write!(f, "AES256"),
+            SseAlgorithm::AwsKms => write!(f, "aws:kms"),
+            SseAlgorithm::AwsKmsDsse => write!(f, "aws:kms:dsse"),
+        }
+    }
+}
+
+impl Serialize for SseAlgorithm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SseAlgorithm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        variant_from_text(
+            deserializer,
+            &[
+                ("AES256", SseAlgorithm::Aes256),
+                ("aws:kms", SseAlgorithm::AwsKms),
+                ("aws:kms:dsse", SseAlgorithm::AwsKmsDsse),
+            ],
+        )
+    }
+}
+
+/// Static website hosting configuration of a bucket
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "WebsiteConfiguration")]
+pub struct WebsiteConfiguration {
+    #[serde(rename = "ErrorDocument", skip_serializing_if = "Option::is_none")]
+    pub error_document: Option<ErrorDocument>,
+    #[serde(rename = "IndexDocument", skip_serializing_if = "Option::is_none")]
+    pub index_document: Option<IndexDocument>,
+    #[serde(
+        rename = "RedirectAllRequestsTo",
+        skip_serializing_if = "Option::is_none"
+    )]
+    /// Excludes all other settings when present.
+    pub redirect_all_requests_to: Option<RedirectAllRequestsTo>,
+    #[serde(rename = "RoutingRules", skip_serializing_if = "Option::is_none")]
+    pub routing_rules: Option<RoutingRules>,
+}
+
+impl WebsiteConfiguration {
+    /// Serve `index_suffix` for directory requests and `error_key` on errors.
+    pub fn new(index_suffix: impl Into<String>, error_key: Option<String>) -> Self {
+        WebsiteConfiguration {
+            error_document: error_key.map(|key| ErrorDocument { key }),
+            index_document: Some(IndexDocument {
+                suffix: index_suffix.into(),
+            }),
+            redirect_all_requests_to: None,
+            routing_rules: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for WebsiteConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let xml = quick_xml::se::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", xml)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IndexDocument {
+    #[serde(rename = "Suffix")]
+    /// Appended to requests for a directory, `index.html` for example.
+    pub suffix: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ErrorDocument {
+    #[serde(rename = "Key")]
+    /// Key of the object returned on 4XX errors.
+    pub key: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RedirectAllRequestsTo {
+    #[serde(rename = "HostName")]
+    pub host_name: String,
+    #[serde(rename = "Protocol", skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RoutingRules {
+    #[serde(rename = "RoutingRule", default)]
+    pub rules: Vec<RoutingRule>,
+}
+
+/// Redirects requests matching `condition`, or all requests if there is none
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoutingRule {
+    #[serde(rename = "Condition", skip_serializing_if = "Option::is_none")]
+    pub condition: Option<RoutingRuleCondition>,
+    #[serde(rename = "Redirect")]
+    pub redirect: RoutingRuleRedirect,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RoutingRuleCondition {
+    #[serde(
+        rename = "HttpErrorCodeReturnedEquals",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub http_error_code_returned_equals: Option<String>,
+    #[serde(rename = "KeyPrefixEquals", skip_serializing_if = "Option::is_none")]
+    pub key_prefix_equals: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RoutingRuleRedirect {
+    #[serde(rename = "HostName", skip_serializing_if = "Option::is_none")]
+    pub host_name: Option<String>,
+    #[serde(rename = "HttpRedirectCode", skip_serializing_if = "Option::is_none")]
+    pub http_redirect_code: Option<String>,
+    #[serde(rename = "Protocol", skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    #[serde(
+        rename = "ReplaceKeyPrefixWith",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub replace_key_prefix_with: Option<String>,
+    #[serde(rename = "ReplaceKeyWith", skip_serializing_if = "Option::is_none")]
+    pub replace_key_with: Option<String>,
+}
+
+/// Body of a `SelectObjectContent` request
+#[derive(Clone, Debug)]
+pub struct SelectObjectContentRequest {
+    /// SQL expression, `SELECT * FROM S3Object s WHERE s._1 = 'x'` for example.
+    pub expression: String,
+    pub input_serialization: SelectInputSerialization,
+    pub output_serialization: SelectOutputSerialization,
+}
+
+impl SelectObjectContentRequest {
+    pub fn new(
+        expression: impl Into<String>,
+        input_serialization: SelectInputSerialization,
+        output_serialization: SelectOutputSerialization,
+    ) -> Self {
+        SelectObjectContentRequest {
+            expression: expression.into(),
+            input_serialization,
+            output_serialization,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for SelectObjectContentRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<SelectObjectContentRequest>")?;
+        write!(f, "<Expression>{}</Expression>", escape(&self.expression))?;
+        write!(f, "<ExpressionType>SQL</ExpressionType>")?;
+        write!(f, "{}", self.input_serialization)?;
+        write!(f, "{}", self.output_serialization)?;
+        write!(f, "</SelectObjectContentRequest>")
+    }
+}
+
+/// Format of the object queried with `SelectObjectContent`
+#[derive(Clone, Debug)]
+pub struct SelectInputSerialization {
+    /// NONE | GZIP | BZIP2
+    pub compression_type: Option<String>,
+    pub format: SelectInputFormat,
+}
+
+impl SelectInputSerialization {
+    pub fn new(format: SelectInputFormat) -> Self {
+        SelectInputSerialization {
+            compression_type: None,
+            format,
+        }
+    }
+}
+
+impl fmt::Display for SelectInputSerialization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<InputSerialization>")?;
+        if let Some(ref compression_type) = self.compression_type {
+            write!(f, "<CompressionType>{}</CompressionType>", compression_type)?;
+        }
+        match self.format {
+            SelectInputFormat::Csv {
+                ref file_header_info,
+                ref field_delimiter,
+            } => {
+                write!(f, "<CSV>")?;
+                if let Some(file_header_info) = file_header_info {
+                    write!(f, "<FileHeaderInfo>{}</FileHeaderInfo>", file_header_info)?;
+                }
+                if let Some(field_delimiter) = field_delimiter {
+                    write!(
+                        f,
+                        "<FieldDelimiter>{}</FieldDelimiter>",
+                        escape(field_delimiter)
+                    )?;
+                }
+                write!(f, "</CSV>")?;
+            }
+            SelectInputFormat::JsonDocument => write!(f, "<JSON><Type>DOCUMENT</Type></JSON>")?,
+            SelectInputFormat::JsonLines => write!(f, "<JSON><Type>LINES</Type></JSON>")?,
+            SelectInputFormat::Parquet => write!(f, "<Parquet></Parquet>")?,
+        }
+        write!(f, "</InputSerialization>")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum SelectInputFormat {
+    Csv {
+        /// USE | IGNORE | NONE, whether the first line holds column names
+        file_header_info: Option<String>,
+        field_delimiter: Option<String>,
+    },
+    JsonDocument,
+    JsonLines,
+    Parquet,
+}
+
+/// Format of the records returned by `SelectObjectContent`
+#[derive(Clone, Debug)]
+pub enum SelectOutputSerialization {
+    Csv,
+    /// One JSON object per line.
+    Json,
+}
+
+impl fmt::Display for SelectOutputSerialization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectOutputSerialization::Csv => {
+                write!(f, "<OutputSerialization><CSV></CSV></OutputSerialization>")
+            }
+            SelectOutputSerialization::Json => {
+                write!(
+                    f,
+                    "<OutputSerialization><JSON></JSON></OutputSerialization>"
+                )
+            }
+        }
+    }
+}
+
+/// Retrieval speed of a `RestoreObject` request, faster tiers cost more
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreTier {
+    Expedited,
+    Standard,
+    Bulk,
+}
+
+impl fmt::Display for RestoreTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestoreTier::Expedited => write!(f, "Expedited"),
+            RestoreTier::Standard => write!(f, "Standard"),
+            RestoreTier::Bulk => write!(f, "Bulk"),
+        }
+    }
+}
+
+/// Body of a `RestoreObject` request
+#[derive(Debug, Clone)]
+pub struct RestoreRequest {
+    /// Lifetime of the restored copy, must be `None` for objects in `INTELLIGENT_TIERING`.
+    pub days: Option<u32>,
+    pub tier: Option<RestoreTier>,
+}
+
+impl RestoreRequest {
+    pub fn new(days: u32, tier: RestoreTier) -> Self {
+        RestoreRequest {
+            days: Some(days),
+            tier: Some(tier),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to_string().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl fmt::Display for RestoreRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<RestoreRequest>")?;
+        if let Some(days) = self.days {
+            write!(f, "<Days>{}</Days>", days)?;
+        }
+        if let Some(tier) = self.tier {
+            write!(
+                f,
+                "<GlacierJobParameters><Tier>{}</Tier></GlacierJobParameters>",
+                tier
+            )?;
+        }
+        write!(f, "</RestoreRequest>")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        AbortIncompleteMultipartUpload, AccessControlPolicy, CompleteMultipartUploadData,
+        ContentRange, CopyObjectResult, CorsConfiguration, CorsRule, CorsRuleBuilder,
+        DefaultRetention, DeleteObjectsData, DeleteObjectsResult, FilterTag,
+        GetObjectAttributesResult, Grant, Grantee, LegalHoldStatus, LifecycleConfiguration,
+        LifecycleExpiration, LifecycleFilter, LifecycleRule, LifecycleRuleStatus, ListPartsResult,
+        MfaDelete, MultipartUpload, MultipartUploadState, ObjectConditions, ObjectIdentifier,
+        ObjectLegalHold, ObjectLockConfiguration, ObjectRetention, Owner, Part, Payer, Permission,
+        ReplicationConfiguration, ReplicationRule, ReplicationStatus, RequestPaymentConfiguration,
+        RestoreRequest, RestoreStatus, RestoreTier, RetentionMode, RoutingRule,
+        RoutingRuleCondition, RoutingRuleRedirect, RoutingRules, SelectInputFormat,
+        SelectInputSerialization, SelectObjectContentRequest, SelectOutputSerialization,
+        ServerSideEncryptionConfiguration, SseAlgorithm, StorageClass, Transition,
+        VersioningConfiguration, VersioningStatus, WebsiteConfiguration,
+    };
+    use crate::checksum::{ChecksumAlgorithm, ChecksumValue};
+
+    #[test]
+    fn multipart_upload_state_resume() {
+        let part_size = 5 * 1024 * 1024;
+        assert!(MultipartUploadState::new("/big.file", "upload", 0).is_err());
+        assert!(MultipartUploadState::new("/big.file", "upload", part_size - 1).is_err());
+        let mut state = MultipartUploadState::new("/big.file", "upload", part_size).unwrap();
+        for part_number in &[2, 1, 4] {
+            state.parts.push(Part {
+                part_number: *part_number,
+                etag: format!("\"etag-{}\"", part_number),
+                checksum: None,
+            });
+        }
+        assert_eq!(state.completed_parts(), 2);
+        assert_eq!(state.resume_offset(), 2 * part_size as u64);
+
+        let json = serde_json::to_string(&state).unwrap();
+        let mut state: MultipartUploadState = serde_json::from_str(&json).unwrap();
+        state.truncate_to_completed();
+        assert_eq!(
+            state
+                .parts
+                .iter()
+                .map(|part| part.part_number)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn content_range_parse() {
+        let range = ContentRange::parse("bytes 0-1023/146515").unwrap();
+        assert_eq!(range.start, 0);
+        assert_eq!(range.end, 1023);
+        assert_eq!(range.total, Some(146515));
+        assert_eq!(range.length(), 1024);
+
+        assert_eq!(ContentRange::parse("bytes 5-5/*").unwrap().total, None);
+        assert_eq!(ContentRange::parse("bytes */146515"), None);
+        assert_eq!(ContentRange::parse("0-1023/146515"), None);
+    }
+
+    #[test]
+    fn object_conditions_headers() {
+        let conditions = ObjectConditions::new()
+            .if_none_match("\"9b2cf535f27731c974343645a3985328\"")
+            .if_modified_since(time::OffsetDateTime::from_unix_timestamp(1255369800).unwrap());
+        let mut headers = http::HeaderMap::new();
+        conditions.add_headers(&mut headers).unwrap();
+
+        assert_eq!(
+            headers["if-none-match"],
+            "\"9b2cf535f27731c974343645a3985328\""
+        );
+        assert_eq!(
+            headers["if-modified-since"],
+            "Mon, 12 Oct 2009 17:50:00 GMT"
+        );
+    }
+
+    #[test]
+    fn storage_class_round_trip() {
+        for class in &["STANDARD_IA", "GLACIER_IR", "DEEP_ARCHIVE", "COLD"] {
+            let parsed: StorageClass = class.parse().unwrap();
+            assert_eq!(parsed.to_string(), *class);
+        }
+        assert_eq!(
+            "ONEZONE_IA".parse::<StorageClass>().unwrap(),
+            StorageClass::OnezoneIa
+        );
+    }
+
+    #[test]
+    fn cors_config_serde() {
+        let rule = CorsRule {
             allowed_headers: Some(vec!["Authorization".to_string(), "Header2".to_string()]),
             allowed_methods: vec!["GET".to_string(), "DELETE".to_string()],
             allowed_origins: vec!["*".to_string()],
@@ -396,4 +2578,515 @@ mod test {
             r#"<CORSConfiguration><CORSRule><AllowedHeader>Authorization</AllowedHeader><AllowedHeader>Header2</AllowedHeader><AllowedMethod>GET</AllowedMethod><AllowedMethod>DELETE</AllowedMethod><AllowedOrigin>*</AllowedOrigin><ID>lala</ID></CORSRule><CORSRule><AllowedHeader>Authorization</AllowedHeader><AllowedHeader>Header2</AllowedHeader><AllowedMethod>GET</AllowedMethod><AllowedMethod>DELETE</AllowedMethod><AllowedOrigin>*</AllowedOrigin><ID>lala</ID></CORSRule></CORSConfiguration>"#
         )
     }
+
+    #[test]
+    fn cors_rule_builder() {
+        use crate::command::HttpMethod;
+
+        let rule = CorsRuleBuilder::default()
+            .allowed_origin("https://example.com")
+            .allowed_method(HttpMethod::Get)
+            .allowed_method(HttpMethod::Put)
+            .max_age_seconds(3600)
+            .build();
+        let config = CorsConfiguration::new(vec![rule]);
+
+        assert_eq!(
+            config.to_string(),
+            "<CORSConfiguration><CORSRule><AllowedMethod>GET</AllowedMethod><AllowedMethod>PUT</AllowedMethod><AllowedOrigin>https://example.com</AllowedOrigin><MaxAgeSeconds>3600</MaxAgeSeconds></CORSRule></CORSConfiguration>"
+        );
+
+        let parsed: CorsConfiguration = quick_xml::de::from_str(&config.to_string()).unwrap();
+        assert_eq!(parsed.rules[0].allowed_methods, vec!["GET", "PUT"]);
+        assert_eq!(parsed.rules[0].max_age_seconds, Some(3600));
+    }
+
+    #[test]
+    fn copy_object_result_deserialize() {
+        let response = r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <CopyObjectResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <LastModified>2009-10-28T22:32:00.000Z</LastModified>
+                <ETag>"9b2cf535f27731c974343645a3985328"</ETag>
+            </CopyObjectResult>
+        "#;
+
+        let result: CopyObjectResult = quick_xml::de::from_str(response).unwrap();
+        assert_eq!(result.e_tag, "\"9b2cf535f27731c974343645a3985328\"");
+        assert_eq!(result.last_modified, "2009-10-28T22:32:00.000Z");
+    }
+
+    #[test]
+    fn delete_objects_data_serialize() {
+        let data = DeleteObjectsData {
+            quiet: false,
+            objects: vec![
+                ObjectIdentifier::new("a&b.txt"),
+                ObjectIdentifier {
+                    key: "c.txt".to_string(),
+                    version_id: Some("v1".to_string()),
+                },
+            ],
+        };
+
+        assert_eq!(
+            data.to_string(),
+            "<Delete><Quiet>false</Quiet><Object><Key>a&amp;b.txt</Key></Object><Object><Key>c.txt</Key><VersionId>v1</VersionId></Object></Delete>"
+        );
+        assert_eq!(data.len(), data.to_string().len());
+    }
+
+    #[test]
+    fn delete_objects_result_deserialize() {
+        let response = r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <DeleteResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <Deleted>
+                    <Key>sample1.txt</Key>
+                </Deleted>
+                <Error>
+                    <Key>sample2.txt</Key>
+                    <Code>AccessDenied</Code>
+                    <Message>Access Denied</Message>
+                </Error>
+            </DeleteResult>
+        "#;
+
+        let result: DeleteObjectsResult = quick_xml::de::from_str(response).unwrap();
+        assert_eq!(result.deleted.len(), 1);
+        assert_eq!(result.deleted[0].key, "sample1.txt");
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].key, "sample2.txt");
+        assert_eq!(result.errors[0].code, "AccessDenied");
+    }
+
+    #[test]
+    fn versioning_configuration_serde() {
+        let config = VersioningConfiguration::new(VersioningStatus::Enabled);
+        assert_eq!(
+            config.to_string(),
+            "<VersioningConfiguration><Status>Enabled</Status></VersioningConfiguration>"
+        );
+
+        let response = r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <VersioningConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <Status>Suspended</Status>
+                <MfaDelete>Disabled</MfaDelete>
+            </VersioningConfiguration>
+        "#;
+        let config: VersioningConfiguration = quick_xml::de::from_str(response).unwrap();
+        assert_eq!(config.status, Some(VersioningStatus::Suspended));
+        assert_eq!(config.mfa_delete, Some(MfaDelete::Disabled));
+
+        let response =
+            r#"<VersioningConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/"/>"#;
+        let config: VersioningConfiguration = quick_xml::de::from_str(response).unwrap();
+        assert_eq!(config, VersioningConfiguration::default());
+    }
+
+    #[test]
+    fn request_payment_configuration_serde() {
+        let config = RequestPaymentConfiguration::new(Payer::Requester);
+        assert_eq!(
+            config.to_string(),
+            "<RequestPaymentConfiguration><Payer>Requester</Payer></RequestPaymentConfiguration>"
+        );
+
+        let response = r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <RequestPaymentConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <Payer>BucketOwner</Payer>
+            </RequestPaymentConfiguration>
+        "#;
+        let config: RequestPaymentConfiguration = quick_xml::de::from_str(response).unwrap();
+        assert_eq!(config.payer, Payer::BucketOwner);
+    }
+
+    #[test]
+    fn object_lock_configuration_serde() {
+        let config =
+            ObjectLockConfiguration::new(DefaultRetention::days(RetentionMode::Governance, 30));
+        assert_eq!(
+            config.to_string(),
+            "<ObjectLockConfiguration><ObjectLockEnabled>Enabled</ObjectLockEnabled><Rule><DefaultRetention><Mode>GOVERNANCE</Mode><Days>30</Days></DefaultRetention></Rule></ObjectLockConfiguration>"
+        );
+
+        let response = r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <ObjectLockConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <ObjectLockEnabled>Enabled</ObjectLockEnabled>
+                <Rule>
+                    <DefaultRetention>
+                        <Mode>COMPLIANCE</Mode>
+                        <Years>7</Years>
+                    </DefaultRetention>
+                </Rule>
+            </ObjectLockConfiguration>
+        "#;
+        let config: ObjectLockConfiguration = quick_xml::de::from_str(response).unwrap();
+        assert_eq!(config.object_lock_enabled.as_deref(), Some("Enabled"));
+        assert_eq!(
+            config.rule.unwrap().default_retention,
+            DefaultRetention::years(RetentionMode::Compliance, 7)
+        );
+    }
+
+    #[test]
+    fn object_retention_and_legal_hold_serde() {
+        let retention = ObjectRetention::new(RetentionMode::Compliance, "2030-01-01T00:00:00Z");
+        assert_eq!(
+            retention.to_string(),
+            "<Retention><Mode>COMPLIANCE</Mode><RetainUntilDate>2030-01-01T00:00:00Z</RetainUntilDate></Retention>"
+        );
+        let parsed: ObjectRetention = quick_xml::de::from_str(&retention.to_string()).unwrap();
+        assert_eq!(parsed, retention);
+
+        let legal_hold = ObjectLegalHold::new(LegalHoldStatus::On);
+        assert_eq!(
+            legal_hold.to_string(),
+            "<LegalHold><Status>ON</Status></LegalHold>"
+        );
+        let parsed: ObjectLegalHold = quick_xml::de::from_str(&legal_hold.to_string()).unwrap();
+        assert_eq!(parsed.status, LegalHoldStatus::On);
+    }
+
+    #[test]
+    fn access_control_policy_serde() {
+        let response = r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <AccessControlPolicy xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <Owner>
+                    <ID>75aa57f09aa0c8caeab4f8c24e99d10f8e7faeebf76c078efc7c6caea54ba06a</ID>
+                    <DisplayName>CustomersName@amazon.com</DisplayName>
+                </Owner>
+                <AccessControlList>
+                    <Grant>
+                        <Grantee xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="CanonicalUser">
+                            <ID>75aa57f09aa0c8caeab4f8c24e99d10f8e7faeebf76c078efc7c6caea54ba06a</ID>
+                            <DisplayName>CustomersName@amazon.com</DisplayName>
+                        </Grantee>
+                        <Permission>FULL_CONTROL</Permission>
+                    </Grant>
+                </AccessControlList>
+            </AccessControlPolicy>
+        "#;
+        let policy: AccessControlPolicy = quick_xml::de::from_str(response).unwrap();
+        assert_eq!(
+            policy.owner.display_name.as_deref(),
+            Some("CustomersName@amazon.com")
+        );
+        let grants = &policy.access_control_list.grants;
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].grantee.grantee_type, "CanonicalUser");
+        assert_eq!(grants[0].permission, Permission::FullControl);
+
+        let owner = Owner {
+            display_name: None,
+            id: "owner-id".to_string(),
+        };
+        let policy = AccessControlPolicy::new(
+            owner,
+            vec![Grant::new(Grantee::all_users(), Permission::Read)],
+        );
+        assert_eq!(
+            policy.to_string(),
+            "<AccessControlPolicy><Owner><ID>owner-id</ID></Owner><AccessControlList><Grant><Grantee xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:type=\"Group\"><URI>http://acs.amazonaws.com/groups/global/AllUsers</URI></Grantee><Permission>READ</Permission></Grant></AccessControlList></AccessControlPolicy>"
+        );
+    }
+
+    #[test]
+    fn lifecycle_configuration_serde() {
+        let mut rule = LifecycleRule::new("logs", LifecycleFilter::prefix("logs/"));
+        rule.expiration = Some(LifecycleExpiration::days(365));
+        rule.transitions.push(Transition::days(30, "GLACIER"));
+        rule.abort_incomplete_multipart_upload = Some(AbortIncompleteMultipartUpload {
+            days_after_initiation: 7,
+        });
+        let config = LifecycleConfiguration::new(vec![rule]);
+
+        assert_eq!(
+            config.to_string(),
+            "<LifecycleConfiguration><Rule><Expiration><Days>365</Days></Expiration><ID>logs</ID><Filter><Prefix>logs/</Prefix></Filter><Status>Enabled</Status><Transition><Days>30</Days><StorageClass>GLACIER</StorageClass></Transition><AbortIncompleteMultipartUpload><DaysAfterInitiation>7</DaysAfterInitiation></AbortIncompleteMultipartUpload></Rule></LifecycleConfiguration>"
+        );
+
+        let response = r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <LifecycleConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <Rule>
+                    <ID>tagged</ID>
+                    <Filter>
+                        <And>
+                            <Prefix>tmp/</Prefix>
+                            <Tag><Key>a</Key><Value>1</Value></Tag>
+                            <Tag><Key>b</Key><Value>2</Value></Tag>
+                        </And>
+                    </Filter>
+                    <Status>Disabled</Status>
+                    <Expiration><Days>1</Days></Expiration>
+                </Rule>
+            </LifecycleConfiguration>
+        "#;
+        let config: LifecycleConfiguration = quick_xml::de::from_str(response).unwrap();
+        let rule = &config.rules[0];
+        assert_eq!(rule.status, LifecycleRuleStatus::Disabled);
+        assert_eq!(rule.expiration.as_ref().unwrap().days, Some(1));
+        let and = rule.filter.as_ref().unwrap().and.as_ref().unwrap();
+        assert_eq!(and.prefix.as_deref(), Some("tmp/"));
+        assert_eq!(
+            and.tags,
+            vec![FilterTag::new("a", "1"), FilterTag::new("b", "2")]
+        );
+    }
+
+    #[test]
+    fn replication_configuration_serde() {
+        let rule = ReplicationRule::new("all", "arn:aws:s3:::destination");
+        let config =
+            ReplicationConfiguration::new("arn:aws:iam::123456789012:role/replication", vec![rule]);
+
+        assert_eq!(
+            config.to_string(),
+            "<ReplicationConfiguration><Role>arn:aws:iam::123456789012:role/replication</Role><Rule><ID>all</ID><Filter/><Status>Enabled</Status><Destination><Bucket>arn:aws:s3:::destination</Bucket></Destination><DeleteMarkerReplication><Status>Disabled</Status></DeleteMarkerReplication></Rule></ReplicationConfiguration>"
+        );
+
+        let response = r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <ReplicationConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <Role>arn:aws:iam::123456789012:role/replication</Role>
+                <Rule>
+                    <ID>docs</ID>
+                    <Priority>2</Priority>
+                    <Filter><Prefix>docs/</Prefix></Filter>
+                    <Status>Enabled</Status>
+                    <Destination>
+                        <Bucket>arn:aws:s3:::destination</Bucket>
+                        <StorageClass>STANDARD_IA</StorageClass>
+                    </Destination>
+                    <DeleteMarkerReplication><Status>Enabled</Status></DeleteMarkerReplication>
+                </Rule>
+            </ReplicationConfiguration>
+        "#;
+        let config: ReplicationConfiguration = quick_xml::de::from_str(response).unwrap();
+        let rule = &config.rules[0];
+        assert_eq!(rule.priority, Some(2));
+        assert_eq!(
+            rule.filter.as_ref().unwrap().prefix.as_deref(),
+            Some("docs/")
+        );
+        assert_eq!(
+            rule.destination.storage_class.as_deref(),
+            Some("STANDARD_IA")
+        );
+        assert_eq!(
+            rule.delete_marker_replication.as_ref().unwrap().status,
+            ReplicationStatus::Enabled
+        );
+    }
+
+    #[test]
+    fn server_side_encryption_configuration_serde() {
+        let config = ServerSideEncryptionConfiguration::kms(Some("key-id".to_string()), true);
+        assert_eq!(
+            config.to_string(),
+            "<ServerSideEncryptionConfiguration><Rule><ApplyServerSideEncryptionByDefault><SSEAlgorithm>aws:kms</SSEAlgorithm><KMSMasterKeyID>key-id</KMSMasterKeyID></ApplyServerSideEncryptionByDefault><BucketKeyEnabled>true</BucketKeyEnabled></Rule></ServerSideEncryptionConfiguration>"
+        );
+
+        let response = r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <ServerSideEncryptionConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <Rule>
+                    <ApplyServerSideEncryptionByDefault>
+                        <SSEAlgorithm>AES256</SSEAlgorithm>
+                    </ApplyServerSideEncryptionByDefault>
+                    <BucketKeyEnabled>false</BucketKeyEnabled>
+                </Rule>
+            </ServerSideEncryptionConfiguration>
+        "#;
+        let config: ServerSideEncryptionConfiguration = quick_xml::de::from_str(response).unwrap();
+        let rule = &config.rules[0];
+        assert_eq!(
+            rule.apply_server_side_encryption_by_default.sse_algorithm,
+            SseAlgorithm::Aes256
+        );
+        assert_eq!(rule.bucket_key_enabled, Some(false));
+    }
+
+    #[test]
+    fn website_configuration_serde() {
+        let mut config = WebsiteConfiguration::new("index.html", Some("404.html".to_string()));
+        config.routing_rules = Some(RoutingRules {
+            rules: vec![RoutingRule {
+                condition: Some(RoutingRuleCondition {
+                    key_prefix_equals: Some("docs/".to_string()),
+                    ..Default::default()
+                }),
+                redirect: RoutingRuleRedirect {
+                    replace_key_prefix_with: Some("documents/".to_string()),
+                    ..Default::default()
+                },
+            }],
+        });
+
+        assert_eq!(
+            config.to_string(),
+            "<WebsiteConfiguration><ErrorDocument><Key>404.html</Key></ErrorDocument><IndexDocument><Suffix>index.html</Suffix></IndexDocument><RoutingRules><RoutingRule><Condition><KeyPrefixEquals>docs/</KeyPrefixEquals></Condition><Redirect><ReplaceKeyPrefixWith>documents/</ReplaceKeyPrefixWith></Redirect></RoutingRule></RoutingRules></WebsiteConfiguration>"
+        );
+
+        let parsed: WebsiteConfiguration = quick_xml::de::from_str(&config.to_string()).unwrap();
+        assert_eq!(parsed.index_document.unwrap().suffix, "index.html");
+        assert_eq!(parsed.error_document.unwrap().key, "404.html");
+        assert_eq!(parsed.routing_rules.unwrap().rules.len(), 1);
+    }
+
+    #[test]
+    fn select_object_content_request_serialize() {
+        let request = SelectObjectContentRequest::new(
+            "SELECT s.name FROM S3Object s WHERE s.age > '30'",
+            SelectInputSerialization::new(SelectInputFormat::Csv {
+                file_header_info: Some("USE".to_string()),
+                field_delimiter: None,
+            }),
+            SelectOutputSerialization::Json,
+        );
+
+        assert_eq!(
+            request.to_string(),
+            "<SelectObjectContentRequest><Expression>SELECT s.name FROM S3Object s WHERE s.age &gt; &apos;30&apos;</Expression><ExpressionType>SQL</ExpressionType><InputSerialization><CSV><FileHeaderInfo>USE</FileHeaderInfo></CSV></InputSerialization><OutputSerialization><JSON></JSON></OutputSerialization></SelectObjectContentRequest>"
+        );
+    }
+
+    #[test]
+    fn restore_request_serialize() {
+        let request = RestoreRequest::new(2, RestoreTier::Bulk);
+        assert_eq!(
+            request.to_string(),
+            "<RestoreRequest><Days>2</Days><GlacierJobParameters><Tier>Bulk</Tier></GlacierJobParameters></RestoreRequest>"
+        );
+    }
+
+    #[test]
+    fn restore_status_parse() {
+        let status = RestoreStatus::parse("ongoing-request=\"true\"");
+        assert!(status.ongoing_request);
+        assert_eq!(status.expiry_date, None);
+
+        let status = RestoreStatus::parse(
+            "ongoing-request=\"false\", expiry-date=\"Fri, 21 Dec 2012 00:00:00 GMT\"",
+        );
+        assert!(!status.ongoing_request);
+        assert_eq!(
+            status.expiry_date.as_deref(),
+            Some("Fri, 21 Dec 2012 00:00:00 GMT")
+        );
+    }
+
+    #[test]
+    fn list_parts_result_deserialize() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListPartsResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Bucket>example-bucket</Bucket>
+  <Key>example-object</Key>
+  <UploadId>XXBsb2FkIElEIGZvciBlbHZpbmcncyVcdS1tb3ZpZS5tMnRzEEEwbG9hZA</UploadId>
+  <PartNumberMarker>1</PartNumberMarker>
+  <NextPartNumberMarker>3</NextPartNumberMarker>
+  <MaxParts>2</MaxParts>
+  <IsTruncated>true</IsTruncated>
+  <Part>
+    <PartNumber>2</PartNumber>
+    <LastModified>2010-11-10T20:48:34.000Z</LastModified>
+    <ETag>"7778aef83f66abc1fa1e8477f296d394"</ETag>
+    <Size>10485760</Size>
+  </Part>
+  <Part>
+    <PartNumber>3</PartNumber>
+    <LastModified>2010-11-10T20:48:33.000Z</LastModified>
+    <ETag>"aaaa18db4cc2f85cedef654fccc4a4x8"</ETag>
+    <Size>10485760</Size>
+    <ChecksumCRC32C>yZRlqg==</ChecksumCRC32C>
+  </Part>
+  <StorageClass>STANDARD</StorageClass>
+</ListPartsResult>"#;
+        let result: ListPartsResult = quick_xml::de::from_str(body).unwrap();
+        assert!(result.is_truncated);
+        assert_eq!(result.next_part_number_marker, Some(3));
+        assert_eq!(result.parts.len(), 2);
+
+        let part: Part = result.parts[0].clone().into();
+        assert_eq!(part.checksum, None);
+        let part: Part = result.parts[1].clone().into();
+        assert_eq!(part.part_number, 3);
+        assert_eq!(part.etag, "\"aaaa18db4cc2f85cedef654fccc4a4x8\"");
+        assert_eq!(
+            part.checksum,
+            Some(ChecksumValue {
+                algorithm: ChecksumAlgorithm::Crc32c,
+                value: "yZRlqg==".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn complete_multipart_upload_checksums() {
+        let data = CompleteMultipartUploadData {
+            parts: vec![
+                Part {
+                    part_number: 1,
+                    etag: "\"etag-1\"".to_string(),
+                    checksum: Some(ChecksumValue::new(ChecksumAlgorithm::Crc32, b"hello world")),
+                },
+                Part {
+                    part_number: 2,
+                    etag: "\"etag-2\"".to_string(),
+                    checksum: None,
+                },
+            ],
+        };
+        assert_eq!(
+            data.to_string(),
+            "<CompleteMultipartUpload>\
+            <Part><PartNumber>1</PartNumber><ETag>\"etag-1\"</ETag><ChecksumCRC32>DUoRhQ==</ChecksumCRC32></Part>\
+            <Part><PartNumber>2</PartNumber><ETag>\"etag-2\"</ETag></Part>\
+            </CompleteMultipartUpload>"
+        );
+    }
+
+    #[test]
+    fn multipart_upload_initiated_at() {
+        let upload = MultipartUpload {
+            initiated: "2010-11-10T20:48:33.000Z".to_string(),
+            storage_class: "STANDARD".to_string(),
+            key: "example-object".to_string(),
+            owner: None,
+            id: "abc".to_string(),
+        };
+        assert_eq!(upload.initiated_at().unwrap().unix_timestamp(), 1289422113);
+    }
+
+    #[test]
+    fn get_object_attributes_result_deserialize() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<GetObjectAttributesResponse xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <ETag>d41d8cd98f00b204e9800998ecf8427e-2</ETag>
+  <Checksum><ChecksumSHA256>2Kb8bD2XxcSe9OTWDqxCLIjBpCMhAo/DD3RgNHAaXqI=-2</ChecksumSHA256></Checksum>
+  <ObjectParts>
+    <IsTruncated>false</IsTruncated>
+    <MaxParts>1000</MaxParts>
+    <PartNumberMarker>0</PartNumberMarker>
+    <NextPartNumberMarker>2</NextPartNumberMarker>
+    <Part><ChecksumSHA256>xzE6DUm1DDRVhE3oKDWB/VdSUBn3pmp0WmINkq9XfmY=</ChecksumSHA256><PartNumber>1</PartNumber><Size>5242880</Size></Part>
+    <Part><ChecksumSHA256>B9YxJS1FJlL6qs6gr6ZBqxHYQ3EzLmUdOyv6smz4jGE=</ChecksumSHA256><PartNumber>2</PartNumber><Size>1024</Size></Part>
+    <PartsCount>2</PartsCount>
+  </ObjectParts>
+  <StorageClass>STANDARD</StorageClass>
+  <ObjectSize>5243904</ObjectSize>
+</GetObjectAttributesResponse>"#;
+        let result: GetObjectAttributesResult = quick_xml::de::from_str(body).unwrap();
+        assert_eq!(result.object_size, Some(5243904));
+        assert!(result.checksum.unwrap().sha256.is_some());
+        let parts = result.object_parts.unwrap();
+        assert_eq!(parts.parts_count, Some(2));
+        assert_eq!(parts.parts.len(), 2);
+        assert_eq!(parts.parts[1].size, 1024);
+    }
 }
@@ -0,0 +1,193 @@
+//! Server side encryption settings that are sent as request headers
+
+use std::collections::HashMap;
+use std::fmt;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use http::header::HeaderName;
+use http::HeaderMap;
+
+use crate::error::S3Error;
+
+/// Customer provided AES-256 key for SSE-C
+///
+/// S3 encrypts the object with the key but doesn't store it, every request that reads or
+/// copies the object has to send the same key again.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SseCustomerKey {
+    key: String,
+    key_md5: String,
+}
+
+impl SseCustomerKey {
+    /// Wrap the raw 256 bit key, which is sent base64 encoded along with its MD5.
+    pub fn new(key: &[u8; 32]) -> SseCustomerKey {
+        SseCustomerKey {
+            key: general_purpose::STANDARD.encode(key),
+            key_md5: general_purpose::STANDARD.encode(md5::compute(key).as_ref()),
+        }
+    }
+
+    /// Add the headers for the object that is written or read.
+    pub fn add_headers(&self, headers: &mut HeaderMap) -> Result<(), S3Error> {
+        self.insert_headers(headers, "x-amz-server-side-encryption-customer-")
+    }
+
+    /// Add the headers for the source object of a copy.
+    pub fn add_copy_source_headers(&self, headers: &mut HeaderMap) -> Result<(), S3Error> {
+        self.insert_headers(
+            headers,
+            "x-amz-copy-source-server-side-encryption-customer-",
+        )
+    }
+
+    fn insert_headers(&self, headers: &mut HeaderMap, prefix: &str) -> Result<(), S3Error> {
+        headers.insert(
+            HeaderName::from_bytes(format!("{}algorithm", prefix).as_bytes())?,
+            "AES256".parse()?,
+        );
+        headers.insert(
+            HeaderName::from_bytes(format!("{}key", prefix).as_bytes())?,
+            self.key.parse()?,
+        );
+        headers.insert(
+            HeaderName::from_bytes(format!("{}key-md5", prefix).as_bytes())?,
+            self.key_md5.parse()?,
+        );
+        Ok(())
+    }
+}
+
+/// Server side encryption with a KMS key (SSE-KMS)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SseKms {
+    /// Key ID or ARN, S3 uses the AWS managed `aws/s3` key when `None`.
+    pub key_id: Option<String>,
+    /// Encryption context, bound to the data key and logged with every KMS call.
+    pub context: Option<HashMap<String, String>>,
+    /// Use an S3 bucket key to cut down on KMS requests, the bucket default when `None`.
+    pub bucket_key_enabled: Option<bool>,
+}
+
+impl SseKms {
+    pub fn new() -> SseKms {
+        SseKms::default()
+    }
+
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> SseKms {
+        self.key_id = Some(key_id.into());
+        self
+    }
+
+    pub fn with_context(mut self, context: HashMap<String, String>) -> SseKms {
+        self.context = Some(context);
+        self
+    }
+
+    pub fn with_bucket_key(mut self, enabled: bool) -> SseKms {
+        self.bucket_key_enabled = Some(enabled);
+        self
+    }
+
+    pub fn add_headers(&self, headers: &mut HeaderMap) -> Result<(), S3Error> {
+        headers.insert(
+            HeaderName::from_static("x-amz-server-side-encryption"),
+            "aws:kms".parse()?,
+        );
+        if let Some(key_id) = &self.key_id {
+            headers.insert(
+                HeaderName::from_static("x-amz-server-side-encryption-aws-kms-key-id"),
+                key_id.parse()?,
+            );
+        }
+        if let Some(context) = &self.context {
+            headers.insert(
+                HeaderName::from_static("x-amz-server-side-encryption-context"),
+                general_purpose::STANDARD
+                    .encode(serde_json::to_string(context)?)
+                    .parse()?,
+            );
+        }
+        if let Some(enabled) = self.bucket_key_enabled {
+            headers.insert(
+                HeaderName::from_static("x-amz-server-side-encryption-bucket-key-enabled"),
+                enabled.to_string().parse()?,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Keeps the key itself out of logs
+impl fmt::Debug for SseCustomerKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SseCustomerKey")
+            .field("key_md5", &self.key_md5)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SseCustomerKey, SseKms};
+    use http::HeaderMap;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_sse_kms_headers() {
+        let mut context = HashMap::new();
+        context.insert("tenant".to_string(), "a".to_string());
+        let sse = SseKms::new()
+            .with_key_id("alias/logs")
+            .with_context(context)
+            .with_bucket_key(true);
+        let mut headers = HeaderMap::new();
+        sse.add_headers(&mut headers).unwrap();
+
+        assert_eq!(headers["x-amz-server-side-encryption"], "aws:kms");
+        assert_eq!(
+            headers["x-amz-server-side-encryption-aws-kms-key-id"],
+            "alias/logs"
+        );
+        // base64 of {"tenant":"a"}
+        assert_eq!(
+            headers["x-amz-server-side-encryption-context"],
+            "eyJ0ZW5hbnQiOiJhIn0="
+        );
+        assert_eq!(
+            headers["x-amz-server-side-encryption-bucket-key-enabled"],
+            "true"
+        );
+
+        let mut headers = HeaderMap::new();
+        SseKms::new().add_headers(&mut headers).unwrap();
+        assert_eq!(headers.len(), 1);
+    }
+
+    #[test]
+    fn test_sse_customer_key_headers() {
+        let key = SseCustomerKey::new(&[0; 32]);
+        let mut headers = HeaderMap::new();
+        key.add_headers(&mut headers).unwrap();
+        key.add_copy_source_headers(&mut headers).unwrap();
+
+        assert_eq!(
+            headers["x-amz-server-side-encryption-customer-algorithm"],
+            "AES256"
+        );
+        assert_eq!(
+            headers["x-amz-server-side-encryption-customer-key"],
+            "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA="
+        );
+        assert_eq!(
+            headers["x-amz-server-side-encryption-customer-key-md5"],
+            "cLyPS3KoaSFGi/joRB3OUQ=="
+        );
+        assert_eq!(
+            headers["x-amz-copy-source-server-side-encryption-customer-key-md5"],
+            "cLyPS3KoaSFGi/joRB3OUQ=="
+        );
+        assert!(!format!("{:?}", key).contains("AAAA"));
+    }
+}
@@ -0,0 +1,211 @@
+//! TLS settings for private endpoints
+
+use std::path::Path;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+
+use crate::error::S3Error;
+
+/// Extra trust roots and a client certificate for the HTTP client
+///
+/// Certificates and keys are PEM encoded, root certificates are trusted on top of the
+/// default ones. native-tls needs a PKCS#8 private key (`BEGIN PRIVATE KEY`), rustls also
+/// takes PKCS#1 and SEC1 keys.
+///
+/// Obeyed by the hyper (`with-tokio`) backend, the attohttpc (`sync`) backend takes root
+/// certificates but no client certificate. The surf backend ignores both, and can't accept
+/// invalid certificates.
+///
+/// ```no_run
+/// use s3::tls::TlsConfig;
+///
+/// let tls_config = TlsConfig::default()
+///     .with_root_certificate_file("/etc/ssl/internal-ca.pem")
+///     .unwrap()
+///     .with_identity_files("/etc/ssl/client.pem", "/etc/ssl/client.key")
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// One PEM certificate per entry.
+    pub root_certificates: Vec<Vec<u8>>,
+    pub identity: Option<Identity>,
+    /// Skip certificate and hostname verification, `no-verify-ssl` for a single client.
+    pub accept_invalid_certs: bool,
+}
+
+/// Client certificate presented to endpoints requiring mutual TLS
+#[derive(Clone, PartialEq, Eq)]
+pub struct Identity {
+    /// PEM certificate chain, the client certificate first.
+    pub certificate_chain: Vec<u8>,
+    /// PEM private key of the client certificate.
+    pub private_key: Vec<u8>,
+}
+
+impl std::fmt::Debug for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Identity")
+            .field(
+                "certificate_chain",
+                &String::from_utf8_lossy(&self.certificate_chain),
+            )
+            .field("private_key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl TlsConfig {
+    /// Trust every certificate of the PEM bundle `pem`.
+    pub fn with_root_certificate_pem(mut self, pem: &[u8]) -> Result<TlsConfig, S3Error> {
+        let certificates = pem_blocks(pem)?
+            .into_iter()
+            .filter(|block| block.label == "CERTIFICATE")
+            .map(|block| block.pem)
+            .collect::<Vec<_>>();
+        if certificates.is_empty() {
+            return Err(S3Error::Tls("no certificate in PEM".to_string()));
+        }
+        self.root_certificates.extend(certificates);
+        Ok(self)
+    }
+
+    /// Trust every certificate of the PEM bundle at `path`.
+    pub fn with_root_certificate_file(self, path: impl AsRef<Path>) -> Result<TlsConfig, S3Error> {
+        let pem = std::fs::read(path)?;
+        self.with_root_certificate_pem(&pem)
+    }
+
+    /// Present `certificate_chain` to endpoints asking for a client certificate.
+    pub fn with_identity_pem(mut self, certificate_chain: &[u8], private_key: &[u8]) -> TlsConfig {
+        self.identity = Some(Identity {
+            certificate_chain: certificate_chain.to_vec(),
+            private_key: private_key.to_vec(),
+        });
+        self
+    }
+
+    /// Present the certificate chain at `certificate_path` to endpoints asking for a client
+    /// certificate.
+    pub fn with_identity_files(
+        self,
+        certificate_path: impl AsRef<Path>,
+        private_key_path: impl AsRef<Path>,
+    ) -> Result<TlsConfig, S3Error> {
+        let certificate_chain = std::fs::read(certificate_path)?;
+        let private_key = std::fs::read(private_key_path)?;
+        Ok(self.with_identity_pem(&certificate_chain, &private_key))
+    }
+
+    /// Accept any certificate for any host, like the `no-verify-ssl` feature does for every
+    /// client. Meant for development against self-signed endpoints, anyone in the middle can
+    /// read and change the traffic.
+    pub fn with_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> TlsConfig {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Whether certificates go unverified with `tls_config`, the feature applies to all.
+    #[cfg(any(
+        feature = "tokio-rustls-tls",
+        feature = "use-tokio-native-tls",
+        feature = "sync-native-tls",
+        feature = "sync-native-tls-vendored",
+        feature = "sync-rustls-tls"
+    ))]
+    pub(crate) fn accepts_invalid_certs(tls_config: Option<&TlsConfig>) -> bool {
+        cfg!(feature = "no-verify-ssl")
+            || tls_config.is_some_and(|config| config.accept_invalid_certs)
+    }
+}
+
+/// A `-----BEGIN <label>-----` section of a PEM file
+#[derive(Debug)]
+pub(crate) struct PemBlock {
+    pub label: String,
+    /// The section itself, markers included.
+    pub pem: Vec<u8>,
+    #[cfg_attr(
+        not(any(feature = "tokio-rustls-tls", feature = "sync-rustls-tls")),
+        allow(dead_code)
+    )]
+    pub der: Vec<u8>,
+}
+
+/// Sections of `pem`, in order. Text around them is skipped like openssl does.
+pub(crate) fn pem_blocks(pem: &[u8]) -> Result<Vec<PemBlock>, S3Error> {
+    let pem = std::str::from_utf8(pem)?;
+    let mut blocks = Vec::new();
+    let mut rest = pem;
+    while let Some(start) = rest.find("-----BEGIN ") {
+        let section = &rest[start..];
+        let label_end = section[11..]
+            .find("-----")
+            .ok_or_else(|| S3Error::Tls("unterminated PEM BEGIN line".to_string()))?;
+        let label = &section[11..11 + label_end];
+        let body_start = 11 + label_end + 5;
+        let end_marker = format!("-----END {}-----", label);
+        let end = section[body_start..]
+            .find(&end_marker)
+            .map(|end| body_start + end)
+            .ok_or_else(|| S3Error::Tls(format!("missing {}", end_marker)))?;
+        let body = section[body_start..end]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        let der = general_purpose::STANDARD
+            .decode(body)
+            .map_err(|e| S3Error::Tls(format!("invalid PEM {}: {}", label, e)))?;
+        let pem_end = end + end_marker.len();
+        blocks.push(PemBlock {
+            label: label.to_string(),
+            pem: section.as_bytes()[..pem_end].to_vec(),
+            der,
+        });
+        rest = &section[pem_end..];
+    }
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{pem_blocks, TlsConfig};
+
+    const BUNDLE: &str = "first CA
+-----BEGIN CERTIFICATE-----
+AAEC
+-----END CERTIFICATE-----
+-----BEGIN CERTIFICATE-----
+AwQF
+Bg==
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_pem_blocks() {
+        let blocks = pem_blocks(BUNDLE.as_bytes()).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].label, "CERTIFICATE");
+        assert_eq!(blocks[0].der, vec![0, 1, 2]);
+        assert_eq!(blocks[1].der, vec![3, 4, 5, 6]);
+        assert!(blocks[1].pem.starts_with(b"-----BEGIN CERTIFICATE-----"));
+        assert!(blocks[1].pem.ends_with(b"-----END CERTIFICATE-----"));
+
+        assert!(pem_blocks(b"-----BEGIN CERTIFICATE-----\nAAEC\n").is_err());
+        // The END marker has to follow the BEGIN line
+        assert!(pem_blocks(b"-----BEGIN X-----END X-----").is_err());
+    }
+
+    #[test]
+    fn test_root_certificates() {
+        let tls_config = TlsConfig::default()
+            .with_root_certificate_pem(BUNDLE.as_bytes())
+            .unwrap();
+        assert_eq!(tls_config.root_certificates.len(), 2);
+
+        assert!(TlsConfig::default()
+            .with_root_certificate_pem(b"not a certificate")
+            .is_err());
+    }
+}
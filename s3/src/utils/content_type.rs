@@ -0,0 +1,76 @@
+use std::path::Path;
+
+/// Used when the extension is missing or unknown, same as `put_object`.
+pub const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Guess the MIME type of `path` (an object key or a local file) from its extension
+///
+/// Covers the types a static site or a log archive is made of, anything else is
+/// `application/octet-stream`.
+///
+/// ```
+/// use s3::utils::content_type_from_path;
+///
+/// assert_eq!(content_type_from_path("/assets/app.js"), "text/javascript");
+/// assert_eq!(content_type_from_path("README"), "application/octet-stream");
+/// ```
+pub fn content_type_from_path(path: impl AsRef<Path>) -> &'static str {
+    let extension = match path.as_ref().extension().and_then(|e| e.to_str()) {
+        Some(extension) => extension.to_ascii_lowercase(),
+        None => return DEFAULT_CONTENT_TYPE,
+    };
+    match extension.as_str() {
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "json" | "map" => "application/json",
+        "webmanifest" => "application/manifest+json",
+        "xml" => "application/xml",
+        "txt" | "log" => "text/plain",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "wasm" => "application/wasm",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "tar" => "application/x-tar",
+        _ => DEFAULT_CONTENT_TYPE,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::content_type_from_path;
+
+    #[test]
+    fn test_content_type_from_path() {
+        assert_eq!(content_type_from_path("index.html"), "text/html");
+        assert_eq!(content_type_from_path("/img/Logo.PNG"), "image/png");
+        assert_eq!(content_type_from_path("site/fonts/a.woff2"), "font/woff2");
+        assert_eq!(
+            content_type_from_path("logs/2023-01-02.log.gz"),
+            "application/gzip"
+        );
+        assert_eq!(
+            content_type_from_path("archive.unknown"),
+            "application/octet-stream"
+        );
+        assert_eq!(content_type_from_path(".env"), "application/octet-stream");
+    }
+}
@@ -0,0 +1,268 @@
+//! Decoder for the `application/vnd.amazon.eventstream` framing used by `SelectObjectContent`
+//!
+//! Every message is laid out as
+//! `total length (4) | headers length (4) | prelude crc (4) | headers | payload | message crc (4)`,
+//! all integers big endian.
+
+use std::collections::HashMap;
+
+use bytes::{Buf, Bytes, BytesMut};
+
+use crate::error::S3Error;
+
+const PRELUDE_LEN: usize = 12;
+const CRC_LEN: usize = 4;
+
+/// A single decoded event stream message
+#[derive(Debug, Clone)]
+pub struct EventStreamMessage {
+    /// String valued headers, `:message-type` and `:event-type` among them.
+    pub headers: HashMap<String, String>,
+    pub payload: Bytes,
+}
+
+impl EventStreamMessage {
+    /// `event`, or `error` / `exception` if the request failed mid stream.
+    pub fn message_type(&self) -> Option<&str> {
+        self.headers.get(":message-type").map(String::as_str)
+    }
+
+    /// `Records`, `Stats`, `Progress`, `Cont` or `End` for messages of type `event`.
+    pub fn event_type(&self) -> Option<&str> {
+        self.headers.get(":event-type").map(String::as_str)
+    }
+
+    /// Turn `error` and `exception` messages into an `S3Error`.
+    pub fn into_result(self) -> Result<Self, S3Error> {
+        if self.message_type() == Some("event") {
+            return Ok(self);
+        }
+        let code = self
+            .headers
+            .get(":error-code")
+            .or_else(|| self.headers.get(":exception-type"))
+            .map(String::as_str)
+            .unwrap_or("UnknownError");
+        let message = self
+            .headers
+            .get(":error-message")
+            .cloned()
+            .unwrap_or_else(|| String::from_utf8_lossy(&self.payload).into_owned());
+        Err(S3Error::EventStream(format!("{}: {}", code, message)))
+    }
+}
+
+/// Incrementally splits a byte stream into `EventStreamMessage`s
+///
+/// Feed it body chunks as they arrive with `push`, then drain complete messages with
+/// `next_message` until it returns `None`.
+#[derive(Debug, Default)]
+pub struct EventStreamDecoder {
+    buffer: BytesMut,
+}
+
+impl EventStreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Bytes received but not yet returned as part of a message.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn next_message(&mut self) -> Result<Option<EventStreamMessage>, S3Error> {
+        if self.buffer.len() < PRELUDE_LEN {
+            return Ok(None);
+        }
+
+        let total_len = read_u32(&self.buffer[0..4]) as usize;
+        let headers_len = read_u32(&self.buffer[4..8]) as usize;
+        let prelude_crc = read_u32(&self.buffer[8..12]);
+        if crc32(&self.buffer[0..8]) != prelude_crc {
+            return Err(S3Error::EventStream(
+                "prelude checksum mismatch".to_string(),
+            ));
+        }
+        if total_len < PRELUDE_LEN + headers_len + CRC_LEN {
+            return Err(S3Error::EventStream(format!(
+                "invalid message length {}",
+                total_len
+            )));
+        }
+        if self.buffer.len() < total_len {
+            return Ok(None);
+        }
+
+        let mut message = self.buffer.split_to(total_len).freeze();
+        let message_crc = read_u32(&message[total_len - CRC_LEN..]);
+        if crc32(&message[..total_len - CRC_LEN]) != message_crc {
+            return Err(S3Error::EventStream(
+                "message checksum mismatch".to_string(),
+            ));
+        }
+
+        message.advance(PRELUDE_LEN);
+        let headers = parse_headers(message.split_to(headers_len))?;
+        message.truncate(message.len() - CRC_LEN);
+
+        Ok(Some(EventStreamMessage {
+            headers,
+            payload: message,
+        }))
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn parse_headers(mut bytes: Bytes) -> Result<HashMap<String, String>, S3Error> {
+    let truncated = || S3Error::EventStream("truncated message headers".to_string());
+    let mut headers = HashMap::new();
+
+    while bytes.has_remaining() {
+        let name_len = bytes.get_u8() as usize;
+        if bytes.remaining() < name_len + 1 {
+            return Err(truncated());
+        }
+        let name = String::from_utf8(bytes.split_to(name_len).to_vec())?;
+
+        // Only strings are kept, every other value type is skipped over
+        let value_len = match bytes.get_u8() {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            6 | 7 => {
+                if bytes.remaining() < 2 {
+                    return Err(truncated());
+                }
+                bytes.get_u16() as usize
+            }
+            value_type => {
+                return Err(S3Error::EventStream(format!(
+                    "unknown header value type {}",
+                    value_type
+                )))
+            }
+        };
+        if bytes.remaining() < value_len {
+            return Err(truncated());
+        }
+        let value = bytes.split_to(value_len);
+        if let Ok(value) = std::str::from_utf8(&value) {
+            headers.insert(name, value.to_string());
+        }
+    }
+
+    Ok(headers)
+}
+
+/// CRC-32 (IEEE), as used by the event stream prelude and message checksums
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crc32, EventStreamDecoder};
+
+    fn encode(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+        let mut encoded_headers = Vec::new();
+        for (name, value) in headers {
+            encoded_headers.push(name.len() as u8);
+            encoded_headers.extend_from_slice(name.as_bytes());
+            encoded_headers.push(7);
+            encoded_headers.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            encoded_headers.extend_from_slice(value.as_bytes());
+        }
+
+        let total_len = 12 + encoded_headers.len() + payload.len() + 4;
+        let mut message = Vec::new();
+        message.extend_from_slice(&(total_len as u32).to_be_bytes());
+        message.extend_from_slice(&(encoded_headers.len() as u32).to_be_bytes());
+        let prelude_crc = crc32(&message);
+        message.extend_from_slice(&prelude_crc.to_be_bytes());
+        message.extend_from_slice(&encoded_headers);
+        message.extend_from_slice(payload);
+        let message_crc = crc32(&message);
+        message.extend_from_slice(&message_crc.to_be_bytes());
+        message
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_decode_split_messages() {
+        let mut body = encode(
+            &[(":message-type", "event"), (":event-type", "Records")],
+            b"a,b\n",
+        );
+        body.extend(encode(
+            &[(":message-type", "event"), (":event-type", "End")],
+            b"",
+        ));
+
+        let mut decoder = EventStreamDecoder::new();
+        let mut messages = Vec::new();
+        for chunk in body.chunks(7) {
+            decoder.push(chunk);
+            while let Some(message) = decoder.next_message().unwrap() {
+                messages.push(message);
+            }
+        }
+
+        assert_eq!(decoder.buffered(), 0);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].event_type(), Some("Records"));
+        assert_eq!(&messages[0].payload[..], b"a,b\n");
+        assert_eq!(messages[1].event_type(), Some("End"));
+    }
+
+    #[test]
+    fn test_decode_error_message() {
+        let body = encode(
+            &[
+                (":message-type", "error"),
+                (":error-code", "InvalidQuery"),
+                (":error-message", "bad query"),
+            ],
+            b"",
+        );
+
+        let mut decoder = EventStreamDecoder::new();
+        decoder.push(&body);
+        let message = decoder.next_message().unwrap().unwrap();
+        let error = message.into_result().unwrap_err();
+        assert_eq!(error.to_string(), "event stream: InvalidQuery: bad query");
+    }
+
+    #[test]
+    fn test_decode_rejects_corrupt_message() {
+        let mut body = encode(&[(":message-type", "event")], b"payload");
+        let last = body.len() - 5;
+        body[last] ^= 0xFF;
+
+        let mut decoder = EventStreamDecoder::new();
+        decoder.push(&body);
+        assert!(decoder.next_message().is_err());
+    }
+}
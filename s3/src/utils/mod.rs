@@ -1,11 +1,17 @@
+mod content_type;
+mod event_stream;
 mod time_utils;
 
+pub use content_type::*;
+pub use event_stream::*;
 pub use time_utils::*;
 
 use std::str::FromStr;
 
-use crate::error::S3Error;
+use crate::checksum::{ChecksumAlgorithm, ChecksumValue};
+use crate::error::{ErrorResponse, S3Error};
 use crate::request::ResponseData;
+use crate::serde_types::{parse_http_date, GetObjectResponse, ObjectEncryption, PutObjectResponse};
 use crate::{bucket::CHUNK_SIZE, serde_types::HeadObjectResult};
 
 use std::fs::File;
@@ -16,7 +22,7 @@ use std::path::Path;
 #[cfg(feature = "with-tokio")]
 use tokio::io::{AsyncRead, AsyncReadExt};
 
-#[cfg(feature = "with-async-std")]
+#[cfg(any(feature = "with-async-std", feature = "with-futures"))]
 use futures::io::{AsyncRead, AsyncReadExt};
 
 pub struct PutStreamResponse {
@@ -71,18 +77,66 @@ pub fn etag_for_path(path: impl AsRef<Path>) -> Result<String, S3Error> {
     Ok(etag)
 }
 
+/// A path next to `path` to download it to, before renaming it into place. Unique within the
+/// process, and across processes by the process id.
+#[cfg(any(feature = "with-tokio", feature = "with-async-std", feature = "sync"))]
+pub(crate) fn temp_path(path: &Path) -> std::path::PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(
+        ".{}.{}-{}.part",
+        name,
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Split `size` bytes into inclusive `(first, last)` byte ranges of at most `part_size` bytes.
+pub fn part_ranges(size: u64, part_size: u64) -> Vec<(u64, u64)> {
+    (0..size)
+        .step_by(part_size as usize)
+        .map(|first| (first, std::cmp::min(first + part_size, size) - 1))
+        .collect()
+}
+
 pub fn read_chunk<R: Read>(reader: &mut R) -> Result<Vec<u8>, S3Error> {
-    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
-    let mut take = reader.take(CHUNK_SIZE as u64);
+    read_part(reader, CHUNK_SIZE)
+}
+
+/// Read up to `size` bytes, less only at the end of `reader`.
+pub fn read_part<R: Read>(reader: &mut R, size: usize) -> Result<Vec<u8>, S3Error> {
+    let mut chunk = Vec::with_capacity(size);
+    let mut take = reader.take(size as u64);
     take.read_to_end(&mut chunk)?;
 
     Ok(chunk)
 }
 
-#[cfg(any(feature = "with-tokio", feature = "with-async-std"))]
+#[cfg(any(
+    feature = "with-tokio",
+    feature = "with-async-std",
+    feature = "with-futures"
+))]
 pub async fn read_chunk_async<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8>, S3Error> {
-    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
-    let mut take = reader.take(CHUNK_SIZE as u64);
+    read_part_async(reader, CHUNK_SIZE).await
+}
+
+#[cfg(any(
+    feature = "with-tokio",
+    feature = "with-async-std",
+    feature = "with-futures"
+))]
+pub async fn read_part_async<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    size: usize,
+) -> Result<Vec<u8>, S3Error> {
+    let mut chunk = Vec::with_capacity(size);
+    let mut take = reader.take(size as u64);
     take.read_to_end(&mut chunk).await?;
 
     Ok(chunk)
@@ -145,6 +199,8 @@ impl From<&http::HeaderMap> for HeadObjectResult {
         result.sse_customer_key_md5 =
             headers.get_string("x-amz-server-side-encryption-customer-key-MD5");
         result.ssekms_key_id = headers.get_string("x-amz-server-side-encryption-aws-kms-key-id");
+        result.bucket_key_enabled =
+            headers.get_and_convert("x-amz-server-side-encryption-bucket-key-enabled");
         result.server_side_encryption = headers.get_string("x-amz-server-side-encryption");
         result.storage_class = headers.get_string("x-amz-storage-class");
         result.version_id = headers.get_string("x-amz-version-id");
@@ -153,17 +209,59 @@ impl From<&http::HeaderMap> for HeadObjectResult {
     }
 }
 
+impl From<&ResponseData> for PutObjectResponse {
+    fn from(response_data: &ResponseData) -> Self {
+        let header = |name: &str| response_data.header(name).map(str::to_string);
+        let sse = ObjectEncryption {
+            algorithm: header("x-amz-server-side-encryption"),
+            kms_key_id: header("x-amz-server-side-encryption-aws-kms-key-id"),
+            bucket_key_enabled: header("x-amz-server-side-encryption-bucket-key-enabled")
+                .and_then(|enabled| enabled.parse().ok()),
+            customer_algorithm: header("x-amz-server-side-encryption-customer-algorithm"),
+            customer_key_md5: header("x-amz-server-side-encryption-customer-key-md5"),
+        };
+        let encrypted = sse.algorithm.is_some() || sse.customer_algorithm.is_some();
+        let checksum = ChecksumAlgorithm::ALL.iter().find_map(|algorithm| {
+            Some(ChecksumValue {
+                algorithm: *algorithm,
+                value: header(algorithm.header_name())?,
+            })
+        });
+        PutObjectResponse {
+            etag: header("etag"),
+            version_id: header("x-amz-version-id"),
+            sse: if encrypted { Some(sse) } else { None },
+            checksum,
+            status_code: response_data.status_code(),
+        }
+    }
+}
+
+impl From<ResponseData> for GetObjectResponse {
+    fn from(response_data: ResponseData) -> Self {
+        let header = |name: &str| response_data.header(name).map(str::to_string);
+        GetObjectResponse {
+            content_type: header("content-type"),
+            content_length: header("content-length").and_then(|length| length.parse().ok()),
+            last_modified: header("last-modified").and_then(|date| parse_http_date(&date)),
+            etag: header("etag"),
+            version_id: header("x-amz-version-id"),
+            metadata: response_data.metadata(),
+            status_code: response_data.status_code(),
+            body: response_data.bytes().clone(),
+        }
+    }
+}
+
 pub(crate) fn error_from_response_data(response_data: ResponseData) -> Result<S3Error, S3Error> {
-    let utf8_content = String::from_utf8(response_data.as_slice().to_vec())?;
-    Err(S3Error::HttpFailWithBody(
-        response_data.status_code(),
-        utf8_content,
-    ))
+    Err(ErrorResponse::from_response_data(&response_data).into())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::utils::etag_for_path;
+    use crate::serde_types::HeadObjectResult;
+    use crate::utils::{etag_for_path, part_ranges};
+    use http::header::HeaderMap;
     use std::fs::File;
     use std::io::prelude::*;
     use std::io::Cursor;
@@ -225,4 +323,121 @@ mod test {
         let result = super::read_chunk(&mut blob).unwrap();
         assert_eq!(result.len(), 1_611_392);
     }
+
+    #[test]
+    fn test_part_ranges() {
+        assert_eq!(part_ranges(0, 5), vec![]);
+        assert_eq!(part_ranges(5, 5), vec![(0, 4)]);
+        assert_eq!(part_ranges(12, 5), vec![(0, 4), (5, 9), (10, 11)]);
+    }
+
+    #[test]
+    fn test_head_object_result_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-length", "1024".parse().unwrap());
+        headers.insert("content-type", "text/plain".parse().unwrap());
+        headers.insert(
+            "etag",
+            "\"9b2cf535f27731c974343645a3985328\"".parse().unwrap(),
+        );
+        headers.insert(
+            "last-modified",
+            "Wed, 12 Oct 2009 17:50:00 GMT".parse().unwrap(),
+        );
+        headers.insert("x-amz-storage-class", "STANDARD_IA".parse().unwrap());
+        headers.insert("x-amz-server-side-encryption", "aws:kms".parse().unwrap());
+        headers.insert(
+            "x-amz-server-side-encryption-bucket-key-enabled",
+            "true".parse().unwrap(),
+        );
+        headers.insert("x-amz-meta-owner", "me".parse().unwrap());
+
+        let result = HeadObjectResult::from(&headers);
+        assert_eq!(result.content_length, Some(1024));
+        assert_eq!(result.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(result.storage_class.as_deref(), Some("STANDARD_IA"));
+        assert_eq!(result.server_side_encryption.as_deref(), Some("aws:kms"));
+        assert_eq!(result.bucket_key_enabled, Some(true));
+        assert_eq!(
+            result.last_modified_at().unwrap().unix_timestamp(),
+            1255369800
+        );
+        assert_eq!(result.metadata.unwrap()["owner"], "me");
+    }
+
+    #[test]
+    fn test_put_object_response_from_response_data() {
+        use crate::checksum::ChecksumAlgorithm;
+        use crate::request::ResponseData;
+        use crate::serde_types::PutObjectResponse;
+        use std::collections::HashMap;
+
+        let mut headers = HashMap::new();
+        for (key, value) in [
+            ("ETag", "\"9b2cf535f27731c974343645a3985328\""),
+            ("x-amz-version-id", "3HL4kqtJlcpXroDTDmJ"),
+            ("x-amz-server-side-encryption", "aws:kms"),
+            ("x-amz-server-side-encryption-aws-kms-key-id", "alias/logs"),
+            ("x-amz-checksum-crc32", "DUoRhQ=="),
+        ] {
+            headers.insert(key.to_string(), value.to_string());
+        }
+        let response =
+            PutObjectResponse::from(&ResponseData::new(bytes::Bytes::new(), 200, headers));
+        assert_eq!(
+            response.etag.as_deref(),
+            Some("\"9b2cf535f27731c974343645a3985328\"")
+        );
+        assert_eq!(response.version_id.as_deref(), Some("3HL4kqtJlcpXroDTDmJ"));
+        let sse = response.sse.as_ref().unwrap();
+        assert_eq!(sse.algorithm.as_deref(), Some("aws:kms"));
+        assert_eq!(sse.kms_key_id.as_deref(), Some("alias/logs"));
+        let checksum = response.checksum.as_ref().unwrap();
+        assert_eq!(checksum.algorithm, ChecksumAlgorithm::Crc32);
+        assert_eq!(checksum.value, "DUoRhQ==");
+        assert_eq!(response.status_code(), 200);
+
+        let response =
+            PutObjectResponse::from(&ResponseData::new(bytes::Bytes::new(), 200, HashMap::new()));
+        assert_eq!(response.sse, None);
+        assert_eq!(response.checksum, None);
+    }
+
+    #[test]
+    fn test_get_object_response_from_response_data() {
+        use crate::request::ResponseData;
+        use crate::serde_types::GetObjectResponse;
+        use std::collections::HashMap;
+
+        let mut headers = HashMap::new();
+        for (key, value) in [
+            ("Content-Type", "text/plain"),
+            ("Content-Length", "11"),
+            ("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+            ("ETag", "\"5eb63bbbe01eeed093cb22bb8f5acdc3\""),
+            ("x-amz-version-id", "3HL4kqtJlcpXroDTDmJ"),
+            ("x-amz-meta-Owner", "me"),
+        ] {
+            headers.insert(key.to_string(), value.to_string());
+        }
+        let response = GetObjectResponse::from(ResponseData::new(
+            bytes::Bytes::from_static(b"hello world"),
+            200,
+            headers,
+        ));
+        assert_eq!(response.as_str().unwrap(), "hello world");
+        assert_eq!(response.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(response.content_length, Some(11));
+        assert_eq!(
+            response.last_modified.unwrap().unix_timestamp(),
+            1_445_412_480
+        );
+        assert_eq!(
+            response.etag.as_deref(),
+            Some("\"5eb63bbbe01eeed093cb22bb8f5acdc3\"")
+        );
+        assert_eq!(response.version_id.as_deref(), Some("3HL4kqtJlcpXroDTDmJ"));
+        assert_eq!(response.metadata["owner"], "me");
+        assert_eq!(response.status_code(), 200);
+    }
 }
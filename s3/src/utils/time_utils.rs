@@ -1,12 +1,19 @@
 use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
 use time::OffsetDateTime;
 
+#[cfg(not(all(target_arch = "wasm32", feature = "with-wasm")))]
 fn real_time() -> Result<u64, SystemTimeError> {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|t| t.as_secs())
 }
 
+/// `SystemTime::now` panics on `wasm32-unknown-unknown`, ask JavaScript instead
+#[cfg(all(target_arch = "wasm32", feature = "with-wasm"))]
+fn real_time() -> Result<u64, SystemTimeError> {
+    Ok((js_sys::Date::now() / 1000.0) as u64)
+}
+
 #[cfg(not(test))]
 pub fn current_time() -> Result<u64, SystemTimeError> {
     real_time()